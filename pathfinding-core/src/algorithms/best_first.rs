@@ -0,0 +1,121 @@
+use crate::{heuristic::zero_heuristic, GridMap, HeuristicFn, Position, TileState, TileValues, TraceEvent};
+
+use super::{follow_parents, min_index, ListItem, Progress, Search};
+
+//Expands the open tile with the least f = d_weight*d + h_weight*h, which covers A*, Dijkstra's algorithm,
+//greedy best-first search, weighted A*, and beam search
+#[derive(Default)]
+pub struct BestFirst {
+    d_weight: f32,
+    h_weight: f32,
+    ignore_heuristic: bool,
+    beam_width: Option<usize>, //most open tiles kept, all of them if None
+    progress: Progress,
+    open_list: Vec<ListItem>,
+    parents: Vec<Vec<Option<Position>>>,
+    closed: Vec<Vec<bool>>
+}
+impl BestFirst {
+    pub fn a_star() -> Self {
+        Self {d_weight: 1., h_weight: 1., ..Default::default()}
+    }
+
+    //Dijkstra's algorithm is A* with a heuristic that always evaluates to 0
+    pub fn dijkstra() -> Self {
+        Self {d_weight: 1., h_weight: 1., ignore_heuristic: true, ..Default::default()}
+    }
+
+    //Greedy best-first search ignores the distance travelled and only follows the heuristic
+    pub fn greedy() -> Self {
+        Self {d_weight: 0., h_weight: 1., ..Default::default()}
+    }
+
+    //Weighted A* trades path length for speed by trusting the heuristic w times more than the distance travelled
+    pub fn weighted(w: f32) -> Self {
+        Self {d_weight: 1., h_weight: w, ..Default::default()}
+    }
+
+    //Beam search is A* that only keeps the best few open tiles, so it uses little memory but can miss the best
+    //path or not find one at all
+    pub fn beam(width: usize) -> Self {
+        Self {d_weight: 1., h_weight: 1., beam_width: Some(width.max(1)), ..Default::default()}
+    }
+}
+impl Search for BestFirst {
+    fn init(&mut self, grid: &GridMap, heuristic: HeuristicFn) {
+        self.progress = Progress::new(if self.ignore_heuristic {zero_heuristic} else {heuristic});
+        self.open_list = vec![ListItem::new(grid.start, 0., 0.)];
+        self.parents = vec![vec![None; grid.width]; grid.height];
+        self.closed = vec![vec![false; grid.width]; grid.height];
+    }
+
+    fn expand(&mut self, grid: &GridMap) {
+        if self.open_list.is_empty() {
+            self.progress.finished = true;
+            return;
+        }
+        //List to store all tile changes for this iteration
+        let mut event_list: Vec<TraceEvent> = Vec::new();
+
+        //a) find the tile with the least f in the open list and pop it off
+        self.progress.count_expansion(self.open_list.len());
+        let tile = self.open_list.remove(min_index(&self.open_list, self.d_weight, self.h_weight));
+        let position = tile.position;
+
+        //b) if the tile is the end tile, stop search and build shortest path. Stopping only once the end is popped
+        //rather than when it is first reached keeps the path shortest when steps cost different amounts
+        if position == grid.end {
+            self.progress.finish(follow_parents(&self.parents, position));
+            return;
+        }
+
+        //c) for each neighbor
+        for (neighbor, cost) in grid.neighbors(position) {
+            //1) if the tile is the start or was already closed, skip
+            if neighbor == grid.start || self.closed[neighbor.1][neighbor.0] {continue}
+
+            //2) compute d and h for the neighbor
+            let d = tile.d + cost;
+            let h = grid.estimate(self.progress.heuristic, neighbor, grid.end);
+
+            match self.open_list.iter_mut().find(|check_tile| check_tile.position == neighbor) {
+                //3) if the tile already exists in open_list, update the tile's parent and d if necessary
+                Some(check_tile) => {
+                    if d < check_tile.d {
+                        check_tile.d = d;
+                        check_tile.h = h;
+                        self.parents[neighbor.1][neighbor.0] = Some(position);
+                        event_list.push(TraceEvent::Values(neighbor, TileValues::new(d, h, self.d_weight*d + self.h_weight*h, Some(position))));
+                    }
+                },
+                //4) otherwise, add the tile to the open list
+                None => {
+                    self.parents[neighbor.1][neighbor.0] = Some(position);
+                    self.open_list.push(ListItem::new(neighbor, d, h));
+                    event_list.push(TraceEvent::State(neighbor, TileState::Open));
+                    event_list.push(TraceEvent::Values(neighbor, TileValues::new(d, h, self.d_weight*d + self.h_weight*h, Some(position))));
+                }
+            }
+        }
+        //d) beam search forgets the open tiles with the greatest f past its width, they can still be found again later
+        if let Some(width) = self.beam_width {
+            while self.open_list.len() > width {
+                let dropped = self.open_list.remove(min_index(&self.open_list, -self.d_weight, -self.h_weight));
+                event_list.push(TraceEvent::State(dropped.position, TileState::Unvisited));
+            }
+        }
+
+        //e) add the tile to the closed list
+        self.closed[position.1][position.0] = true;
+        event_list.push(TraceEvent::State(position, TileState::Closed));
+        self.progress.steps.push_back(event_list);
+    }
+
+    fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    fn progress_mut(&mut self) -> &mut Progress {
+        &mut self.progress
+    }
+}
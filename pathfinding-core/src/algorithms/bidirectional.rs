@@ -0,0 +1,89 @@
+use crate::{GridMap, HeuristicFn, Position, TileState, TileValues, TraceEvent};
+
+use super::{follow_parents, min_index, ListItem, Progress, Search};
+
+//Bidirectional A* runs one search forward from the start and one backward from the end, alternating
+//expansions between them until a tile reached by one search is found by the other.
+//Index 0 of each pair belongs to the forward search and index 1 to the backward search
+#[derive(Default)]
+pub struct BidirectionalAStar {
+    progress: Progress,
+    open_lists: [Vec<ListItem>; 2],
+    parents: [Vec<Vec<Option<Position>>>; 2],
+    seen: [Vec<Vec<bool>>; 2],
+    closed: [Vec<Vec<bool>>; 2],
+    side: usize //search that expands next
+}
+impl Search for BidirectionalAStar {
+    fn init(&mut self, grid: &GridMap, heuristic: HeuristicFn) {
+        self.progress = Progress::new(heuristic);
+        self.open_lists = [vec![ListItem::new(grid.start, 0., 0.)], vec![ListItem::new(grid.end, 0., 0.)]];
+        self.parents = [vec![vec![None; grid.width]; grid.height], vec![vec![None; grid.width]; grid.height]];
+        self.seen = [vec![vec![false; grid.width]; grid.height], vec![vec![false; grid.width]; grid.height]];
+        self.closed = [vec![vec![false; grid.width]; grid.height], vec![vec![false; grid.width]; grid.height]];
+        self.seen[0][grid.start.1][grid.start.0] = true;
+        self.seen[1][grid.end.1][grid.end.0] = true;
+        self.side = 0;
+    }
+
+    fn expand(&mut self, grid: &GridMap) {
+        if self.open_lists[0].is_empty() || self.open_lists[1].is_empty() {
+            self.progress.finished = true;
+            return;
+        }
+        let side = self.side;
+        let target = if side==0 {grid.end} else {grid.start};
+        let (open_state, closed_state) = if side==0 {(TileState::Open, TileState::Closed)} else {(TileState::ReverseOpen, TileState::ReverseClosed)};
+        let mut event_list: Vec<TraceEvent> = Vec::new();
+
+        self.progress.count_expansion(self.open_lists[0].len() + self.open_lists[1].len());
+        let tile = self.open_lists[side].remove(min_index(&self.open_lists[side], 1., 1.));
+        let position = tile.position;
+
+        //the backward search walks edges the wrong way, so it follows the cells that can step to this one
+        let edges = if side==0 {grid.neighbors(position)} else {grid.predecessors(position)};
+        for (neighbor, cost) in edges {
+            //the frontiers met, join the two parent chains across this edge
+            if self.seen[1-side][neighbor.1][neighbor.0] {
+                self.progress.steps.push_back(event_list);
+                let (forward, backward) = if side==0 {(position, neighbor)} else {(neighbor, position)};
+                let mut path = follow_parents(&self.parents[0], forward);
+                path.extend(follow_parents(&self.parents[1], backward).into_iter().rev());
+                self.progress.finish(path);
+                return;
+            }
+            if self.closed[side][neighbor.1][neighbor.0] {continue}
+
+            let d = tile.d + cost;
+            let h = grid.estimate(self.progress.heuristic, neighbor, target);
+            if self.seen[side][neighbor.1][neighbor.0] {
+                if let Some(check_tile) = self.open_lists[side].iter_mut().find(|check_tile| check_tile.position == neighbor) {
+                    if d < check_tile.d {
+                        check_tile.d = d;
+                        check_tile.h = h;
+                        self.parents[side][neighbor.1][neighbor.0] = Some(position);
+                        event_list.push(TraceEvent::Values(neighbor, TileValues::new(d, h, d + h, Some(position))));
+                    }
+                }
+            } else {
+                self.seen[side][neighbor.1][neighbor.0] = true;
+                self.parents[side][neighbor.1][neighbor.0] = Some(position);
+                self.open_lists[side].push(ListItem::new(neighbor, d, h));
+                event_list.push(TraceEvent::State(neighbor, open_state));
+                event_list.push(TraceEvent::Values(neighbor, TileValues::new(d, h, d + h, Some(position))));
+            }
+        }
+        self.closed[side][position.1][position.0] = true;
+        event_list.push(TraceEvent::State(position, closed_state));
+        self.progress.steps.push_back(event_list);
+        self.side = 1-side;
+    }
+
+    fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    fn progress_mut(&mut self) -> &mut Progress {
+        &mut self.progress
+    }
+}
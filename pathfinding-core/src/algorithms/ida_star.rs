@@ -0,0 +1,124 @@
+use crate::{GridMap, HeuristicFn, Position, TileState, TileValues, TraceEvent};
+
+use super::{Progress, Search};
+
+//A tile on the current depth-first path along with the neighbors it still has to try
+struct Frame {
+    position: Position,
+    d: f32,
+    neighbors: Vec<(Position, f32)>,
+    next: usize
+}
+
+//IDA* repeats a depth-first search that cuts off any tile with f above a threshold, raising the
+//threshold to the smallest f that was cut off until the end is found
+#[derive(Default)]
+pub struct IdaStar {
+    progress: Progress,
+    threshold: f32,
+    next_threshold: f32, //smallest f cut off during this iteration
+    stack: Vec<Frame>,
+    best_d: Vec<Vec<f32>>,
+    touched: Vec<Position> //tiles shown during this iteration
+}
+impl IdaStar {
+    fn start_iteration(&mut self, grid: &GridMap) {
+        self.progress.steps.push_back(vec![TraceEvent::Threshold(self.threshold)]);
+        self.next_threshold = f32::INFINITY;
+        self.best_d = vec![vec![f32::INFINITY; grid.width]; grid.height];
+        self.touched.clear();
+        self.enter(grid, grid.start, 0.);
+    }
+
+    //Visit a tile at distance d from the start, pushing it onto the stack if the search continues from it
+    fn enter(&mut self, grid: &GridMap, position: Position, d: f32) {
+        let h = grid.estimate(self.progress.heuristic, position, grid.end);
+        let f = d + h;
+        if f > self.threshold {
+            self.next_threshold = self.next_threshold.min(f);
+            return;
+        }
+        if position == grid.end {
+            let mut path: Vec<Position> = self.stack.iter().map(|frame| frame.position).collect();
+            path.push(position);
+            self.progress.finish(path);
+            return;
+        }
+
+        //don't search from a tile again if this iteration already reached it as cheaply, this also prevents cycles
+        if d >= self.best_d[position.1][position.0] {return}
+        self.best_d[position.1][position.0] = d;
+
+        //the depth-first path is the only list IDA* keeps
+        self.progress.count_expansion(self.stack.len()+1);
+        self.touched.push(position);
+        let parent = self.stack.last().map(|frame| frame.position);
+        self.progress.steps.push_back(vec![TraceEvent::State(position, TileState::Open), TraceEvent::Values(position, TileValues::new(d, h, f, parent))]);
+        self.stack.push(Frame {position, d, neighbors: grid.neighbors(position), next: 0});
+    }
+}
+impl Search for IdaStar {
+    fn init(&mut self, grid: &GridMap, heuristic: HeuristicFn) {
+        self.progress = Progress::new(heuristic);
+        self.threshold = grid.estimate(heuristic, grid.start, grid.end);
+        self.stack.clear();
+        //with no path every iteration only raises the threshold a little, and there can be as many iterations
+        //as there are distances to the tiles it can reach, so an end that can't be reached is found out first
+        if !reaches(grid, grid.start, grid.end) {
+            self.progress.finished = true;
+            return;
+        }
+        self.start_iteration(grid);
+    }
+
+    fn expand(&mut self, grid: &GridMap) {
+        let Some(frame) = self.stack.last_mut() else {
+            //the iteration ended without finding the end
+            if self.next_threshold == f32::INFINITY {
+                self.progress.finished = true;
+                return;
+            }
+            //reset every tile this iteration touched before starting the next one
+            self.progress.steps.push_back(self.touched.iter().map(|&position| TraceEvent::State(position, TileState::Unvisited)).collect());
+            self.threshold = self.next_threshold;
+            self.start_iteration(grid);
+            return;
+        };
+
+        if frame.next < frame.neighbors.len() {
+            let (neighbor, cost) = frame.neighbors[frame.next];
+            let d = frame.d + cost;
+            frame.next += 1;
+            self.enter(grid, neighbor, d);
+        } else {
+            let position = frame.position;
+            self.stack.pop();
+            self.progress.steps.push_back(vec![TraceEvent::State(position, TileState::Closed)]);
+        }
+    }
+
+    fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    fn progress_mut(&mut self) -> &mut Progress {
+        &mut self.progress
+    }
+}
+
+//Whether any path leads from one tile to another
+fn reaches(grid: &GridMap, from: Position, to: Position) -> bool {
+    let mut seen = vec![vec![false; grid.width]; grid.height];
+    seen[from.1][from.0] = true;
+    let mut stack = vec![from];
+    while let Some(position) = stack.pop() {
+        if position == to {return true}
+        for (neighbor, _) in grid.neighbors(position) {
+            if !seen[neighbor.1][neighbor.0] {
+                seen[neighbor.1][neighbor.0] = true;
+                stack.push(neighbor);
+            }
+        }
+    }
+    false
+}
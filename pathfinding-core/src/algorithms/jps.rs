@@ -0,0 +1,209 @@
+use crate::{Cell, GridMap, Movement, HeuristicFn, Position, TileState, TileValues, TraceEvent};
+
+use super::{min_index, ListItem, Progress, Search};
+
+//Jump Point Search is A* over jump points, tiles found by scanning in straight lines from a tile until
+//something interesting happens (the end is reached or a wall forces a new direction).
+//Skipping tiles is only safe where every step costs the same and is allowed, so tiles near terrain or one-way tiles,
+//or near walls when the movement restricts diagonal steps, are always jump points. On wrapping grids a scan also stops
+//before it goes halfway around, since past that the way around the other side is shorter. Portals are stopped at like
+//the end, and their other end is a jump point reached through them
+#[derive(Default)]
+pub struct Jps {
+    progress: Progress,
+    open_list: Vec<ListItem>,
+    parents: Vec<Vec<Option<Position>>>,
+    closed: Vec<Vec<bool>>,
+    scanned: Vec<Vec<bool>>
+}
+impl Jps {
+    //Open a jump point reached from the tile being expanded for cost, or give it a shorter path
+    fn add_jump_point(&mut self, grid: &GridMap, tile: &ListItem, jump_point: Position, cost: f32, event_list: &mut Vec<TraceEvent>) {
+        let position = tile.position;
+        if self.closed[jump_point.1][jump_point.0] {return}
+        let d = tile.d + cost;
+        let h = grid.estimate(self.progress.heuristic, jump_point, grid.end);
+        match self.open_list.iter_mut().find(|check_tile| check_tile.position == jump_point) {
+            Some(check_tile) => {
+                if d < check_tile.d {
+                    check_tile.d = d;
+                    check_tile.h = h;
+                    self.parents[jump_point.1][jump_point.0] = Some(position);
+                    event_list.push(TraceEvent::Values(jump_point, TileValues::new(d, h, d + h, Some(position))));
+                }
+            },
+            None => {
+                self.parents[jump_point.1][jump_point.0] = Some(position);
+                self.open_list.push(ListItem::new(jump_point, d, h));
+                event_list.push(TraceEvent::State(jump_point, TileState::JumpPoint));
+                event_list.push(TraceEvent::Values(jump_point, TileValues::new(d, h, d + h, Some(position))));
+            }
+        }
+    }
+}
+impl Search for Jps {
+    fn init(&mut self, grid: &GridMap, heuristic: HeuristicFn) {
+        self.progress = Progress::new(heuristic);
+        self.open_list = vec![ListItem::new(grid.start, 0., 0.)];
+        self.parents = vec![vec![None; grid.width]; grid.height];
+        self.closed = vec![vec![false; grid.width]; grid.height];
+        self.scanned = vec![vec![false; grid.width]; grid.height];
+    }
+
+    fn expand(&mut self, grid: &GridMap) {
+        if self.open_list.is_empty() {
+            self.progress.finished = true;
+            return;
+        }
+        let mut event_list: Vec<TraceEvent> = Vec::new();
+
+        self.progress.count_expansion(self.open_list.len());
+        let tile = self.open_list.remove(min_index(&self.open_list, 1., 1.));
+        let position = tile.position;
+        if position == grid.end {
+            self.progress.finish(jump_path(grid, &self.parents, position));
+            return;
+        }
+        self.closed[position.1][position.0] = true;
+
+        for (dx, dy) in jps_directions(grid, position, self.parents[position.1][position.0]) {
+            let mut scan: Vec<Position> = Vec::new();
+            let jump_point = jump(grid, position, (dx, dy), &mut scan);
+
+            //show every tile crossed by the scan that hasn't been shown yet
+            for (x, y) in scan {
+                if self.scanned[y][x] {continue}
+                self.scanned[y][x] = true;
+                event_list.push(TraceEvent::State((x, y), TileState::Scanned));
+            }
+
+            let Some(jump_point) = jump_point else {continue};
+            self.add_jump_point(grid, &tile, jump_point, grid.line_cost(position, jump_point), &mut event_list);
+        }
+        if let Some(exit) = grid.portal_exit(position) {
+            self.add_jump_point(grid, &tile, exit, grid.edge_cost(position, exit), &mut event_list);
+        }
+        self.progress.steps.push_back(event_list);
+    }
+
+    fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    fn progress_mut(&mut self) -> &mut Progress {
+        &mut self.progress
+    }
+}
+
+//Directions worth scanning from a jump point given the direction it was reached from,
+//the start and tiles near an obstacle scan every direction the movement allows
+fn jps_directions(grid: &GridMap, (x, y): Position, parent: Option<Position>) -> Vec<(i32, i32)> {
+    let Some(parent) = parent.filter(|_| !near_obstacle(grid, x as i32, y as i32)) else {
+        return grid.directions((x, y)).into_iter()
+            .filter(|&(dx, dy)| grid.topology.is_hex() || grid.movement.allows_diagonals() || dx == 0 || dy == 0)
+            .collect();
+    };
+    let (dx, dy) = grid.delta(parent, (x, y));
+    let (dx, dy) = (dx.signum(), dy.signum());
+    let (x, y) = (x as i32, y as i32);
+    let mut directions = vec![(dx, dy)];
+    if dx != 0 && dy != 0 {
+        directions.push((dx, 0));
+        directions.push((0, dy));
+        if !grid.is_walkable(x-dx, y) {directions.push((-dx, dy))}
+        if !grid.is_walkable(x, y-dy) {directions.push((dx, -dy))}
+    } else if dx != 0 {
+        if !grid.is_walkable(x, y+1) {directions.push((dx, 1))}
+        if !grid.is_walkable(x, y-1) {directions.push((dx, -1))}
+        //without diagonals a horizontal jump point is where a vertical scan found something
+        if !grid.movement.allows_diagonals() {
+            directions.push((0, 1));
+            directions.push((0, -1));
+        }
+    } else {
+        if !grid.is_walkable(x+1, y) {directions.push((1, dy))}
+        if !grid.is_walkable(x-1, y) {directions.push((-1, dy))}
+    }
+    directions
+}
+
+//Scan from a tile in one direction and return the first jump point found, every tile crossed is added to scan
+fn jump(grid: &GridMap, from: Position, (dx, dy): (i32, i32), scan: &mut Vec<Position>) -> Option<Position> {
+    let (mut x, mut y) = (from.0 as i32, from.1 as i32);
+    let (limit, mut steps) = (scan_limit(grid, (dx, dy)), 0);
+    loop {
+        if !grid.can_step((x as usize, y as usize), (dx, dy)) {return None}
+        let position = grid.wrap_position(x+dx, y+dy)?;
+        (x, y) = (position.0 as i32, position.1 as i32);
+        steps += 1;
+        scan.push(position);
+        if position == grid.end || near_obstacle(grid, x, y) || steps == limit {return Some(position)}
+
+        //a tile with a forced neighbor is a jump point
+        if dx != 0 && dy != 0 {
+            if (!grid.is_walkable(x-dx, y) && grid.is_walkable(x-dx, y+dy)) || (!grid.is_walkable(x, y-dy) && grid.is_walkable(x+dx, y-dy)) {
+                return Some((x as usize, y as usize));
+            }
+            //diagonal scans stop wherever one of the straight scans they spawn finds a jump point
+            if jump(grid, (x as usize, y as usize), (dx, 0), scan).is_some() || jump(grid, (x as usize, y as usize), (0, dy), scan).is_some() {
+                return Some((x as usize, y as usize));
+            }
+        } else if dx != 0 {
+            if (!grid.is_walkable(x, y+1) && grid.is_walkable(x+dx, y+1)) || (!grid.is_walkable(x, y-1) && grid.is_walkable(x+dx, y-1)) {
+                return Some((x as usize, y as usize));
+            }
+            //without diagonals, paths turn from horizontal to vertical instead, so horizontal scans spawn vertical ones
+            if !grid.movement.allows_diagonals() && (jump(grid, (x as usize, y as usize), (0, 1), scan).is_some() || jump(grid, (x as usize, y as usize), (0, -1), scan).is_some()) {
+                return Some((x as usize, y as usize));
+            }
+        } else if (!grid.is_walkable(x+1, y) && grid.is_walkable(x+1, y+dy)) || (!grid.is_walkable(x-1, y) && grid.is_walkable(x-1, y+dy)) {
+            return Some((x as usize, y as usize));
+        }
+    }
+}
+
+//Most steps a scan can take before the way back around a wrapping edge it crosses would be no longer
+fn scan_limit(grid: &GridMap, (dx, dy): (i32, i32)) -> usize {
+    let mut limit = usize::MAX;
+    if dx != 0 && grid.wrap.wraps_x() {limit = limit.min((grid.width-1)/2)}
+    if dy != 0 && grid.wrap.wraps_y() {limit = limit.min((grid.height-1)/2)}
+    limit
+}
+
+//Whether a tile or one around it breaks the symmetry jumps rely on. Terrain and one-way tiles always do, and for
+//movement other than plain 8-way so do walls and the edge of the grid, since they change which steps are allowed.
+//Hex grids don't have the symmetry at all, so every tile is a jump point and the search is plain A*.
+//A portal leads somewhere scans can't see, so it is a jump point too
+fn near_obstacle(grid: &GridMap, x: i32, y: i32) -> bool {
+    if grid.topology.is_hex() || grid.portal_exit((x as usize, y as usize)).is_some() {return true}
+    (-1..=1).any(|dx| (-1..=1).any(|dy| {
+        let Some((x, y)) = grid.wrap_position(x+dx, y+dy) else {return grid.movement != Movement::EightWay};
+        match grid.cells[y][x] {
+            Cell::Terrain(_) | Cell::OneWay(_) => true,
+            Cell::Wall => grid.movement != Movement::EightWay,
+            Cell::Empty => false
+        }
+    }))
+}
+
+//Follow the jump point parents back to the start, filling in every tile on the lines between them
+fn jump_path(grid: &GridMap, parents: &[Vec<Option<Position>>], from: Position) -> Vec<Position> {
+    let mut path = vec![from];
+    let mut p = from;
+    while let Some(parent) = parents[p.1][p.0] {
+        //going through a portal skips straight to the other end
+        if grid.portal_exit(p) == Some(parent) {
+            p = parent;
+            path.push(p);
+            continue;
+        }
+        let (dx, dy) = grid.delta(p, parent);
+        let (dx, dy) = (dx.signum(), dy.signum());
+        while p != parent {
+            p = grid.wrap_position(p.0 as i32 + dx, p.1 as i32 + dy).unwrap();
+            path.push(p);
+        }
+    }
+    path.reverse();
+    path
+}
@@ -0,0 +1,159 @@
+use crate::{GridMap, HeuristicFn, Position, SearchStats, TileState, TileValues, TraceEvent};
+
+use super::{Progress, Search};
+
+//How far apart two keys can be and still count as a tie
+const KEY_TOLERANCE: f32 = 1e-4;
+
+//Lifelong Planning A* keeps the g values of its last search so that when a wall changes only the tiles
+//whose distance from the start actually changed are expanded again
+#[derive(Default)]
+pub struct LpaStar {
+    progress: Progress,
+    g: Vec<Vec<f32>>, //distance from the start found by the last expansion of each tile
+    rhs: Vec<Vec<f32>>, //one step lookahead of g computed from each tile's neighbors
+    open_list: Vec<Position>, //tiles where g and rhs disagree
+    portals: Vec<(Position, Position)> //portals the g values were found with
+}
+impl Search for LpaStar {
+    fn init(&mut self, grid: &GridMap, heuristic: HeuristicFn) {
+        self.progress = Progress::new(heuristic);
+        self.g = vec![vec![f32::INFINITY; grid.width]; grid.height];
+        self.rhs = self.g.clone();
+        self.rhs[grid.start.1][grid.start.0] = 0.;
+        self.open_list = vec![grid.start];
+        self.portals = grid.portals.clone();
+    }
+
+    //Expand the inconsistent tile with the least key, or trace the path once the end's g is correct.
+    //Tiles whose key ties the end's are expanded too, rounding can leave one on the path with a stale g
+    fn expand(&mut self, grid: &GridMap) {
+        let end = grid.end;
+        let next = self.open_list.iter().enumerate().min_by(|a, b| if self.key(grid, *a.1) < self.key(grid, *b.1) {std::cmp::Ordering::Less} else {std::cmp::Ordering::Greater});
+        let Some((index, &u)) = next.filter(|&(_, &u)| self.key(grid, u).0 <= self.key(grid, end).0 + KEY_TOLERANCE || self.rhs[end.1][end.0] != self.g[end.1][end.0]) else {
+            self.set_path(grid);
+            return;
+        };
+        self.progress.count_expansion(self.open_list.len());
+        self.open_list.remove(index);
+
+        let mut event_list: Vec<TraceEvent> = Vec::new();
+        if self.g[u.1][u.0] > self.rhs[u.1][u.0] {
+            //overconsistent, the tile got closer to the start
+            self.g[u.1][u.0] = self.rhs[u.1][u.0];
+            event_list.push(TraceEvent::State(u, TileState::Closed));
+        } else {
+            //underconsistent, the tile got further from the start so forget its distance and recompute it
+            self.g[u.1][u.0] = f32::INFINITY;
+            event_list.push(TraceEvent::State(u, TileState::Unvisited));
+            self.update_vertex(grid, u, &mut event_list);
+        }
+        for neighbor in neighbors(grid, u) {
+            self.update_vertex(grid, neighbor, &mut event_list);
+        }
+        self.progress.steps.push_back(event_list);
+    }
+
+    //Every edge into and out of the changed tile changed cost, so recompute it and its neighbors
+    fn repair(&mut self, grid: &GridMap, position: Position) -> bool {
+        //nothing to repair before the first search. A portal changing changes edges between tiles far apart, and
+        //the estimate of every tile with them. The two ends of a free portal can hold up each other's old g forever,
+        //so those aren't repaired either
+        if self.g.is_empty() || grid.portals != self.portals || (!grid.portals.is_empty() && grid.portal_cost <= 0.) {return false}
+
+        //show the old path as explored tiles again
+        let mut event_list: Vec<TraceEvent> = self.progress.path.iter().map(|&p| TraceEvent::State(p, TileState::Closed)).collect();
+        self.update_vertex(grid, position, &mut event_list);
+        for neighbor in neighbors(grid, position) {
+            self.update_vertex(grid, neighbor, &mut event_list);
+        }
+        self.progress.steps.push_back(event_list);
+        self.progress.path.clear();
+        self.progress.finished = false;
+        //count only the work of the repair so it can be compared with solving again
+        self.progress.stats = SearchStats::default();
+        true
+    }
+
+    fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    fn progress_mut(&mut self) -> &mut Progress {
+        &mut self.progress
+    }
+}
+impl LpaStar {
+    //Follow the neighbor with the least g + cost back from the end and finish the search with it
+    fn set_path(&mut self, grid: &GridMap) {
+        if self.g[grid.end.1][grid.end.0] == f32::INFINITY {
+            self.progress.finished = true;
+            return;
+        }
+        let mut path = vec![grid.end];
+        let mut p = grid.end;
+        while p != grid.start {
+            let through: Vec<(Position, f32)> = neighbors(grid, p).into_iter().map(|a| (a, self.g[a.1][a.0] + cost(grid, a, p))).collect();
+            let least = through.iter().fold(f32::INFINITY, |least, &(_, d)| least.min(d));
+            //a free portal ties its two ends, so of the neighbors tied for the least the one nearest the start is
+            //taken, otherwise the path could go back and forth through the portal forever
+            p = through.into_iter()
+                .filter(|&(_, d)| d <= least + KEY_TOLERANCE)
+                .min_by(|&(a, _), &(b, _)| self.g[a.1][a.0].total_cmp(&self.g[b.1][b.0]))
+                .unwrap().0;
+            path.push(p);
+        }
+        path.reverse();
+        self.progress.finish(path);
+    }
+
+    //Recompute rhs for a tile and add it to or remove it from the open list depending on whether it is consistent
+    fn update_vertex(&mut self, grid: &GridMap, u: Position, event_list: &mut Vec<TraceEvent>) {
+        if u != grid.start {
+            //the neighbor the least rhs comes through is the tile's parent
+            let (parent, rhs) = neighbors(grid, u).into_iter()
+                .map(|s| (s, self.g[s.1][s.0] + cost(grid, s, u)))
+                .fold((None, f32::INFINITY), |best, (s, d)| if d < best.1 {(Some(s), d)} else {best});
+            if rhs != self.rhs[u.1][u.0] {
+                self.rhs[u.1][u.0] = rhs;
+                let h = grid.estimate(self.progress.heuristic, u, grid.end);
+                event_list.push(TraceEvent::Values(u, TileValues::new(rhs, h, rhs + h, parent)));
+            }
+        }
+        let in_open = self.open_list.contains(&u);
+        if self.g[u.1][u.0] != self.rhs[u.1][u.0] {
+            if !in_open {
+                self.open_list.push(u);
+                event_list.push(TraceEvent::State(u, TileState::Open));
+            }
+        } else if in_open {
+            self.open_list.retain(|&p| p != u);
+            event_list.push(TraceEvent::State(u, if self.g[u.1][u.0] == f32::INFINITY {TileState::Unvisited} else {TileState::Closed}));
+        }
+    }
+
+    fn key(&self, grid: &GridMap, (x, y): Position) -> (f32, f32) {
+        let m = self.g[y][x].min(self.rhs[y][x]);
+        (m + grid.estimate(self.progress.heuristic, (x, y), grid.end), m)
+    }
+}
+
+//All tiles around a position that are inside the grid, walls and steps the movement or one-way tiles don't allow
+//included since their edges just cost infinity, and the other end of a portal the position is on
+fn neighbors(grid: &GridMap, (x, y): Position) -> Vec<Position> {
+    let mut neighbors = Vec::new();
+    for dx in -1..=1 {
+        for dy in -1..=1 {
+            if dx==0 && dy==0 {continue}
+            let Some(neighbor) = grid.wrap_position(x as i32+dx, y as i32+dy) else {continue};
+            neighbors.push(neighbor);
+        }
+    }
+    neighbors.extend(grid.portal_exit((x, y)));
+    neighbors
+}
+
+fn cost(grid: &GridMap, a: Position, b: Position) -> f32 {
+    if !grid.is_walkable(a.0 as i32, a.1 as i32) {return f32::INFINITY}
+    grid.edge_cost(a, b)
+}
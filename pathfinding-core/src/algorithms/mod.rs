@@ -0,0 +1,125 @@
+mod best_first;
+mod bidirectional;
+mod ida_star;
+mod jps;
+mod lpa_star;
+mod theta_star;
+mod uninformed;
+
+pub use best_first::BestFirst;
+pub use bidirectional::BidirectionalAStar;
+pub use ida_star::IdaStar;
+pub use jps::Jps;
+pub use lpa_star::LpaStar;
+pub use theta_star::ThetaStar;
+pub use uninformed::UninformedSearch;
+
+use std::collections::VecDeque;
+
+use crate::{heuristic::zero_heuristic, GridMap, HeuristicFn, Position, SearchStats, Solver, TileState, TraceEvent};
+
+#[derive(Clone)]
+struct ListItem {
+    pub position: Position,
+    pub d: f32, //computed distance from start to tile
+    pub h: f32 //heuristic evaluation of distance from tile to end
+}
+impl ListItem {
+    pub fn new(position: Position, d: f32, h: f32) -> Self {
+        Self {position, d, h}
+    }
+}
+
+//Index of the item in a non-empty list with the least d_weight*d + h_weight*h
+fn min_index(list: &[ListItem], d_weight: f32, h_weight: f32) -> usize {
+    let (index, _) = list.iter().enumerate().min_by(|a, b| if d_weight*a.1.d+h_weight*a.1.h < d_weight*b.1.d+h_weight*b.1.h {std::cmp::Ordering::Less} else {std::cmp::Ordering::Greater}).unwrap();
+    index
+}
+
+//State every solver keeps between steps: the heuristic it was started with, steps it produced but
+//hasn't returned yet, its stats, and the path once the search is finished
+struct Progress {
+    heuristic: HeuristicFn,
+    steps: VecDeque<Vec<TraceEvent>>,
+    stats: SearchStats,
+    path: Vec<Position>,
+    finished: bool
+}
+impl Default for Progress {
+    fn default() -> Self {
+        Self {heuristic: zero_heuristic, steps: VecDeque::new(), stats: SearchStats::default(), path: Vec::new(), finished: false}
+    }
+}
+impl Progress {
+    fn new(heuristic: HeuristicFn) -> Self {
+        Self {heuristic, ..Default::default()}
+    }
+
+    //Count a tile being expanded while open_size tiles were waiting to be
+    fn count_expansion(&mut self, open_size: usize) {
+        self.stats.expanded += 1;
+        self.stats.max_open = self.stats.max_open.max(open_size);
+    }
+
+    //Finish the search with a path and trace it one tile per step, walking back from the end
+    fn finish(&mut self, path: Vec<Position>) {
+        for &position in path.iter().rev() {
+            self.steps.push_back(vec![TraceEvent::State(position, TileState::Path)]);
+        }
+        self.path = path;
+        self.finished = true;
+    }
+}
+
+//What a solver has to provide for the rest of Solver to be the same for all of them: how it starts, how it
+//expands, and where it keeps its progress. Steps are returned as soon as an expansion produces some
+trait Search: Send + Sync {
+    fn init(&mut self, grid: &GridMap, heuristic: HeuristicFn);
+
+    //Expand the search once, adding the steps it produced to the progress or finishing it
+    fn expand(&mut self, grid: &GridMap);
+
+    fn repair(&mut self, _grid: &GridMap, _position: Position) -> bool {
+        false
+    }
+
+    fn progress(&self) -> &Progress;
+
+    fn progress_mut(&mut self) -> &mut Progress;
+}
+impl<T: Search> Solver for T {
+    fn init(&mut self, grid: &GridMap, heuristic: HeuristicFn) {
+        Search::init(self, grid, heuristic);
+    }
+
+    fn step(&mut self, grid: &GridMap) -> Option<Vec<TraceEvent>> {
+        while self.progress().steps.is_empty() && !self.progress().finished {
+            self.expand(grid);
+        }
+        self.progress_mut().steps.pop_front()
+    }
+
+    fn result(&self) -> &[Position] {
+        &self.progress().path
+    }
+
+    fn stats(&self) -> SearchStats {
+        self.progress().stats
+    }
+
+    fn repair(&mut self, grid: &GridMap, position: Position) -> bool {
+        Search::repair(self, grid, position)
+    }
+}
+
+//Follow a parent chain from a position to its root, returning the chain starting at the root
+fn follow_parents(parents: &[Vec<Option<Position>>], from: Position) -> Vec<Position> {
+    let mut chain = vec![from];
+    let mut p = from;
+    while let Some(parent) = parents[p.1][p.0] {
+        chain.push(parent);
+        p = parent;
+    }
+    chain.reverse();
+    chain
+}
@@ -0,0 +1,134 @@
+use crate::{GridMap, HeuristicFn, Position, TileState, TileValues, TraceEvent};
+
+use super::{follow_parents, min_index, ListItem, Progress, Search};
+
+//Theta* lets a tile's parent be any tile it can see, checking line of sight as soon as the tile is reached.
+//Lazy Theta* assumes a tile can see its grandparent and only checks line of sight when the tile is expanded
+#[derive(Default)]
+pub struct ThetaStar {
+    lazy: bool,
+    progress: Progress,
+    open_list: Vec<ListItem>,
+    parents: Vec<Vec<Option<Position>>>,
+    g: Vec<Vec<f32>>,
+    closed: Vec<Vec<bool>>
+}
+impl ThetaStar {
+    pub fn lazy() -> Self {
+        Self {lazy: true, ..Default::default()}
+    }
+
+    //Finish with the corners of the path and trace it as straight lines between them, one segment per step.
+    //Going through a portal isn't a line across the grid, so it gets no segment
+    fn finish_any_angle(&mut self, grid: &GridMap, path: Vec<Position>) {
+        for corners in path.windows(2).rev() {
+            let mut events = vec![TraceEvent::State(corners[0], TileState::Path)];
+            if grid.portal_exit(corners[1]) != Some(corners[0]) {
+                events.insert(0, TraceEvent::Segment(corners[1], corners[0]));
+            }
+            self.progress.steps.push_back(events);
+        }
+        self.progress.path = path;
+        self.progress.finished = true;
+    }
+}
+impl Search for ThetaStar {
+    fn init(&mut self, grid: &GridMap, heuristic: HeuristicFn) {
+        self.progress = Progress::new(heuristic);
+        self.open_list = vec![ListItem::new(grid.start, 0., 0.)];
+        self.parents = vec![vec![None; grid.width]; grid.height];
+        self.g = vec![vec![f32::INFINITY; grid.width]; grid.height];
+        self.closed = vec![vec![false; grid.width]; grid.height];
+        self.g[grid.start.1][grid.start.0] = 0.;
+    }
+
+    fn expand(&mut self, grid: &GridMap) {
+        if self.open_list.is_empty() {
+            self.progress.finished = true;
+            return;
+        }
+        let mut event_list: Vec<TraceEvent> = Vec::new();
+
+        let tile = self.open_list.remove(min_index(&self.open_list, 1., 1.));
+        let position = tile.position;
+        if self.closed[position.1][position.0] {return}
+        self.progress.count_expansion(self.open_list.len()+1);
+
+        //Lazy Theta* repairs a parent it can't actually see using the best expanded neighbor
+        if self.lazy {
+            if let Some(parent) = self.parents[position.1][position.0] {
+                if !line_of_sight(grid, parent, position) {
+                    let best = grid.predecessors(position).into_iter()
+                        .filter(|&(neighbor, _)| self.closed[neighbor.1][neighbor.0])
+                        .map(|(neighbor, cost)| (neighbor, self.g[neighbor.1][neighbor.0] + cost))
+                        .min_by(|a, b| if a.1 < b.1 {std::cmp::Ordering::Less} else {std::cmp::Ordering::Greater});
+                    if let Some((parent, d)) = best {
+                        self.parents[position.1][position.0] = Some(parent);
+                        self.g[position.1][position.0] = d;
+                        let h = grid.estimate(self.progress.heuristic, position, grid.end);
+                        event_list.push(TraceEvent::Values(position, TileValues::new(d, h, d + h, Some(parent))));
+                    }
+                }
+            }
+        }
+        self.closed[position.1][position.0] = true;
+
+        if position == grid.end {
+            self.finish_any_angle(grid, follow_parents(&self.parents, position));
+            return;
+        }
+
+        for (neighbor, cost) in grid.neighbors(position) {
+            if self.closed[neighbor.1][neighbor.0] {continue}
+
+            //path 2: go straight from this tile's parent if it can see the neighbor, otherwise path 1: go through this tile.
+            //Going straight can cross more terrain, so path 2 is only taken when it is no more expensive
+            let through = (position, self.g[position.1][position.0] + cost);
+            let (parent, d) = match self.parents[position.1][position.0] {
+                Some(parent) if self.lazy || line_of_sight(grid, parent, neighbor) => {
+                    let straight = (parent, self.g[parent.1][parent.0] + grid.line_cost(parent, neighbor));
+                    if straight.1 <= through.1 {straight} else {through}
+                },
+                _ => through,
+            };
+            if d >= self.g[neighbor.1][neighbor.0] {continue}
+
+            if self.g[neighbor.1][neighbor.0] == f32::INFINITY {
+                event_list.push(TraceEvent::State(neighbor, TileState::Open));
+            }
+            self.g[neighbor.1][neighbor.0] = d;
+            self.parents[neighbor.1][neighbor.0] = Some(parent);
+            let h = grid.estimate(self.progress.heuristic, neighbor, grid.end);
+            self.open_list.retain(|check_tile| check_tile.position != neighbor);
+            self.open_list.push(ListItem::new(neighbor, d, h));
+            event_list.push(TraceEvent::Values(neighbor, TileValues::new(d, h, d + h, Some(parent))));
+        }
+
+        event_list.push(TraceEvent::State(position, TileState::Closed));
+        self.progress.steps.push_back(event_list);
+    }
+
+    fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    fn progress_mut(&mut self) -> &mut Progress {
+        &mut self.progress
+    }
+}
+
+//A tile can see another if no wall is on the segment between their centers, passing through a corner is a
+//diagonal step so the movement has to allow it. One-way tiles on the segment have to be crossed their way.
+//Without diagonal steps a path can only go along rows and columns, so only tiles in line are seen
+fn line_of_sight(grid: &GridMap, from: Position, to: Position) -> bool {
+    if !grid.topology.is_hex() && !grid.movement.allows_diagonals() && from.0 != to.0 && from.1 != to.1 {return false}
+    let mut previous = (from.0 as i32, from.1 as i32);
+    grid.line(from, to).into_iter().all(|(x, y)| {
+        //the line isn't folded across wrapping edges, so the step is taken from the folded cell before it. That cell
+        //is only read if the step onto it was allowed, which means it is on the grid
+        let step = (x - previous.0, y - previous.1);
+        let Some(position) = grid.wrap_position(previous.0, previous.1) else {return false};
+        previous = (x, y);
+        grid.can_step(position, step)
+    })
+}
@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+
+use crate::{GridMap, HeuristicFn, Position, TileState, TileValues, TraceEvent};
+
+use super::{follow_parents, Progress, Search};
+
+//Breadth-first search pops the oldest tile off the frontier, depth-first search pops the newest
+#[derive(Default)]
+pub struct UninformedSearch {
+    depth_first: bool,
+    progress: Progress,
+    frontier: VecDeque<Position>,
+    parents: Vec<Vec<Option<Position>>>,
+    g: Vec<Vec<f32>>, //distance from the start along the parents, only kept to be shown
+    opened: Vec<Vec<bool>>,
+    closed: Vec<Vec<bool>>
+}
+impl UninformedSearch {
+    pub fn bfs() -> Self {
+        Self::default()
+    }
+
+    pub fn dfs() -> Self {
+        Self {depth_first: true, ..Default::default()}
+    }
+}
+impl Search for UninformedSearch {
+    fn init(&mut self, grid: &GridMap, heuristic: HeuristicFn) {
+        self.progress = Progress::new(heuristic);
+        self.frontier = VecDeque::from([grid.start]);
+        self.parents = vec![vec![None; grid.width]; grid.height];
+        self.g = vec![vec![0.; grid.width]; grid.height];
+        self.opened = vec![vec![false; grid.width]; grid.height];
+        self.closed = vec![vec![false; grid.width]; grid.height];
+        self.opened[grid.start.1][grid.start.0] = true;
+    }
+
+    fn expand(&mut self, grid: &GridMap) {
+        let Some(position) = (if self.depth_first {self.frontier.pop_back()} else {self.frontier.pop_front()}) else {
+            self.progress.finished = true;
+            return;
+        };
+        //a depth-first frontier can hold the same tile more than once, only expand it the first time
+        if self.closed[position.1][position.0] {return}
+        self.progress.count_expansion(self.frontier.len()+1);
+        self.closed[position.1][position.0] = true;
+
+        let mut event_list: Vec<TraceEvent> = Vec::new();
+        for (neighbor, cost) in grid.neighbors(position) {
+            let g = self.g[position.1][position.0] + cost;
+            if neighbor == grid.end {
+                self.parents[neighbor.1][neighbor.0] = Some(position);
+                event_list.push(TraceEvent::Values(neighbor, TileValues::new(g, 0., g, Some(position))));
+                self.progress.steps.push_back(event_list);
+                self.progress.finish(follow_parents(&self.parents, neighbor));
+                return;
+            }
+            //breadth-first keeps the first parent found, depth-first takes the newest one
+            if self.closed[neighbor.1][neighbor.0] || (!self.depth_first && self.opened[neighbor.1][neighbor.0]) {continue}
+            self.parents[neighbor.1][neighbor.0] = Some(position);
+            self.g[neighbor.1][neighbor.0] = g;
+            self.frontier.push_back(neighbor);
+            if !self.opened[neighbor.1][neighbor.0] {
+                self.opened[neighbor.1][neighbor.0] = true;
+                event_list.push(TraceEvent::State(neighbor, TileState::Open));
+            }
+            event_list.push(TraceEvent::Values(neighbor, TileValues::new(g, 0., g, Some(position))));
+        }
+        event_list.push(TraceEvent::State(position, TileState::Closed));
+        self.progress.steps.push_back(event_list);
+    }
+
+    fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    fn progress_mut(&mut self) -> &mut Progress {
+        &mut self.progress
+    }
+}
@@ -0,0 +1,359 @@
+use crate::{hex, HeuristicFn, Position};
+
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub enum Cell {
+    #[default]
+    Empty,
+    Wall,
+    Terrain(u8), //walkable, but entering it costs this much more than an empty cell
+    OneWay(Direction) //walkable, but only by steps that go the direction, both into and out of it
+}
+
+//Way a one-way cell lets paths go, up is towards +y
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub enum Direction {
+    Up,
+    #[default]
+    Right,
+    Down,
+    Left
+}
+impl Direction {
+    pub const ALL: [Direction; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn vector(&self) -> (i32, i32) {
+        match self {
+            Self::Up => (0, 1),
+            Self::Right => (1, 0),
+            Self::Down => (0, -1),
+            Self::Left => (-1, 0),
+        }
+    }
+
+    pub fn clockwise(&self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+}
+
+//Which steps between neighboring cells are allowed
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub enum Movement {
+    FourWay,
+    #[default]
+    EightWay,
+    NoCornerCutting, //diagonal steps need both cells beside them to be walkable
+    NoSqueezing //diagonal steps need one of the cells beside them to be walkable
+}
+impl Movement {
+    pub const ALL: [Movement; 4] = [Self::FourWay, Self::EightWay, Self::NoCornerCutting, Self::NoSqueezing];
+
+    pub fn allows_diagonals(&self) -> bool {
+        !matches!(self, Self::FourWay)
+    }
+}
+
+//Shape of the cells and so which cells are next to each other
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub enum Topology {
+    #[default]
+    Square,
+    PointyHex, //hexes with a corner at the top, odd rows are shifted half a hex towards +x
+    FlatHex //hexes with an edge at the top, odd columns are shifted half a hex towards +y
+}
+impl Topology {
+    pub const ALL: [Topology; 3] = [Self::Square, Self::PointyHex, Self::FlatHex];
+
+    pub fn is_hex(&self) -> bool {
+        !matches!(self, Self::Square)
+    }
+}
+
+//Which edges of the grid lead around to the opposite edge
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub enum Wrap {
+    #[default]
+    None,
+    Horizontal, //the left and right edges meet
+    Vertical, //the top and bottom edges meet
+    Both
+}
+impl Wrap {
+    pub const ALL: [Wrap; 4] = [Self::None, Self::Horizontal, Self::Vertical, Self::Both];
+
+    pub fn wraps_x(&self) -> bool {
+        matches!(self, Self::Horizontal | Self::Both)
+    }
+
+    pub fn wraps_y(&self) -> bool {
+        matches!(self, Self::Vertical | Self::Both)
+    }
+
+    //Two positions on a grid of some size moved by whole widths or heights so they are as close as the wrapping
+    //allows. On hex grids that is the fewest steps apart, since the shift of odd rows or columns can make going
+    //around the longer way in one coordinate shorter overall. Only ever moved up, so both stay positive
+    pub fn closest_images(&self, topology: Topology, (width, height): (usize, usize), from: Position, to: Position) -> (Position, Position) {
+        //not moving comes first so that a tie doesn't wrap
+        let moves = |wraps: bool, size: usize, from: usize, to: usize| if wraps {vec![(from, to), (from + size, to), (from, to + size)]} else {vec![(from, to)]};
+        let xs = moves(self.wraps_x(), width, from.0, to.0);
+        let ys = moves(self.wraps_y(), height, from.1, to.1);
+        let images = xs.iter().flat_map(|&(from_x, to_x)| ys.iter().map(move |&(from_y, to_y)| ((from_x, from_y), (to_x, to_y))));
+        images.min_by_key(|&(from, to)| {
+            let apart = from.0.abs_diff(to.0) + from.1.abs_diff(to.1);
+            match topology {
+                Topology::Square => (apart, 0),
+                Topology::PointyHex => (hex::distance(true, from, to), apart),
+                Topology::FlatHex => (hex::distance(false, from, to), apart),
+            }
+        }).unwrap()
+    }
+}
+
+//A grid of cells with a start and an end, rows are indexed by y and columns by x
+#[derive(Clone, Debug)]
+pub struct GridMap {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<Vec<Cell>>,
+    pub start: Position,
+    pub end: Position,
+    pub movement: Movement, //only used by square grids, hexes always step to their 6 neighbors
+    pub topology: Topology,
+    pub wrap: Wrap, //hex grids only line up across a wrapping edge if the rows or columns shifted along it are even
+    pub portals: Vec<(Position, Position)>, //pairs of linked cells, stepping onto one lets a path continue from the other
+    pub portal_cost: f32 //cost of going from one end of a portal to the other
+}
+impl GridMap {
+    pub fn new(width: usize, height: usize, start: Position, end: Position) -> Self {
+        Self {width, height, cells: vec![vec![Cell::Empty; width]; height], start, end, movement: Movement::default(), topology: Topology::default(), wrap: Wrap::default(), portals: Vec::new(), portal_cost: 1.}
+    }
+
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x>=0 && y>=0 && x<self.width as i32 && y<self.height as i32
+    }
+
+    //Fold a position that stepped off a wrapping edge back onto the grid, None if it is off the grid
+    pub fn wrap_position(&self, x: i32, y: i32) -> Option<Position> {
+        let x = if self.wrap.wraps_x() {x.rem_euclid(self.width as i32)} else {x};
+        let y = if self.wrap.wraps_y() {y.rem_euclid(self.height as i32)} else {y};
+        self.in_bounds(x, y).then_some((x as usize, y as usize))
+    }
+
+    pub fn is_walkable(&self, x: i32, y: i32) -> bool {
+        self.wrap_position(x, y).is_some_and(|(x, y)| self.cells[y][x] != Cell::Wall)
+    }
+
+    pub fn closest_images(&self, from: Position, to: Position) -> (Position, Position) {
+        self.wrap.closest_images(self.topology, (self.width, self.height), from, to)
+    }
+
+    //Offset from one cell to another, across a wrapping edge when that is shorter
+    pub fn delta(&self, from: Position, to: Position) -> (i32, i32) {
+        let (from, to) = self.closest_images(from, to);
+        (to.0 as i32 - from.0 as i32, to.1 as i32 - from.1 as i32)
+    }
+
+    //Heuristic estimate between two cells, measured across wrapping edges when that is shorter. With portals it is the
+    //least of going there directly and every route through portals, since a portal can lead much closer
+    pub fn estimate(&self, heuristic: HeuristicFn, from: Position, to: Position) -> f32 {
+        let direct = |from: Position, to: Position| {
+            let (from, to) = self.closest_images(from, to);
+            heuristic(from, to)
+        };
+        if self.portals.is_empty() {return direct(from, to)}
+
+        //every portal end with the end it leads to
+        let hops: Vec<(Position, Position)> = self.portals.iter().flat_map(|&(a, b)| [(a, b), (b, a)]).collect();
+        //estimate from the far end of each hop to the target, through any number of other portals. Found like
+        //Dijkstra, the hop with the least estimate that isn't done yet can't get any less through the others
+        let mut remaining: Vec<f32> = hops.iter().map(|&(_, exit)| direct(exit, to)).collect();
+        let mut done = vec![false; hops.len()];
+        while let Some(next) = (0..hops.len()).filter(|&i| !done[i]).min_by(|&a, &b| remaining[a].total_cmp(&remaining[b])) {
+            done[next] = true;
+            for i in 0..hops.len() {
+                if done[i] {continue}
+                let through = direct(hops[i].1, hops[next].0) + self.portal_cost + remaining[next];
+                remaining[i] = remaining[i].min(through);
+            }
+        }
+        hops.iter().zip(remaining)
+            .map(|(&(entrance, _), remaining)| direct(from, entrance) + self.portal_cost + remaining)
+            .fold(direct(from, to), f32::min)
+    }
+
+    //The other end of the portal a cell is on, if it is on one
+    pub fn portal_exit(&self, position: Position) -> Option<Position> {
+        self.portals.iter().find_map(|&(a, b)| if a == position {Some(b)} else if b == position {Some(a)} else {None})
+    }
+
+    //Offsets from a cell to every cell next to it
+    pub fn directions(&self, position: Position) -> Vec<(i32, i32)> {
+        match self.topology {
+            Topology::Square => (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (dx, dy))).filter(|&d| d != (0, 0)).collect(),
+            Topology::PointyHex => hex::directions(true, position).to_vec(),
+            Topology::FlatHex => hex::directions(false, position).to_vec(),
+        }
+    }
+
+    //Whether a step from a cell goes the way of the one-way cells it leaves and enters, it has to move at least
+    //partly in their direction
+    pub fn follows_one_ways(&self, (x, y): Position, (dx, dy): (i32, i32)) -> bool {
+        let (step_x, step_y) = match self.topology {
+            Topology::Square => (dx as f32, dy as f32),
+            Topology::PointyHex => hex::step_offset(true, (x, y), (dx, dy)),
+            Topology::FlatHex => hex::step_offset(false, (x, y), (dx, dy)),
+        };
+        [Some((x, y)), self.wrap_position(x as i32+dx, y as i32+dy)].into_iter().flatten().all(|(x, y)| match self.cells[y][x] {
+            Cell::OneWay(direction) => {
+                let (along_x, along_y) = direction.vector();
+                step_x*along_x as f32 + step_y*along_y as f32 > 0.
+            },
+            _ => true
+        })
+    }
+
+    //Whether the movement allows stepping from a cell in a direction, the cell stepped to has to be walkable
+    //and the step has to follow the one-way cells it leaves and enters
+    pub fn can_step(&self, (x, y): Position, (dx, dy): (i32, i32)) -> bool {
+        if !self.is_walkable(x as i32+dx, y as i32+dy) || !self.follows_one_ways((x, y), (dx, dy)) {return false}
+        if self.topology.is_hex() {return self.directions((x, y)).contains(&(dx, dy))}
+        if dx == 0 || dy == 0 {return true}
+        let (x, y) = (x as i32, y as i32);
+        //the two cells the diagonal passes between
+        let (beside_x, beside_y) = (self.is_walkable(x+dx, y), self.is_walkable(x, y+dy));
+        match self.movement {
+            Movement::FourWay => false,
+            Movement::EightWay => true,
+            Movement::NoCornerCutting => beside_x && beside_y,
+            Movement::NoSqueezing => beside_x || beside_y,
+        }
+    }
+
+    //Every cell around a position that the movement allows stepping to along with the cost of stepping to it,
+    //and the other end of a portal the position is on
+    pub fn neighbors(&self, (x, y): Position) -> Vec<(Position, f32)> {
+        let mut neighbors = Vec::new();
+        for (dx, dy) in self.directions((x, y)) {
+            if !self.can_step((x, y), (dx, dy)) {continue}
+            let Some(neighbor) = self.wrap_position(x as i32+dx, y as i32+dy) else {continue};
+            neighbors.push((neighbor, self.cost((x, y), neighbor)));
+        }
+        if let Some(exit) = self.portal_exit((x, y)) {
+            neighbors.push((exit, self.portal_cost));
+        }
+        neighbors
+    }
+
+    //Every cell that can step to a position along with the cost of the step, and the other end of a portal the
+    //position is on. One-way cells make these differ from the neighbors
+    pub fn predecessors(&self, (x, y): Position) -> Vec<(Position, f32)> {
+        let mut predecessors = Vec::new();
+        for (dx, dy) in self.directions((x, y)) {
+            let Some(predecessor) = self.wrap_position(x as i32+dx, y as i32+dy) else {continue};
+            if !self.is_walkable(predecessor.0 as i32, predecessor.1 as i32) || !self.can_step(predecessor, (-dx, -dy)) {continue}
+            predecessors.push((predecessor, self.cost(predecessor, (x, y))));
+        }
+        if let Some(exit) = self.portal_exit((x, y)) {
+            predecessors.push((exit, self.portal_cost));
+        }
+        predecessors
+    }
+
+    //Cost of the cheapest way to move from a cell straight to one of its neighbors, infinity if there is none
+    pub fn edge_cost(&self, from: Position, to: Position) -> f32 {
+        self.neighbors(from).into_iter()
+            .filter(|&(neighbor, _)| neighbor == to)
+            .fold(f32::INFINITY, |least, (_, cost)| least.min(cost))
+    }
+
+    //Extra cost of entering a cell on top of the distance moved
+    pub fn terrain_cost(&self, (x, y): Position) -> f32 {
+        match self.cells[y][x] {
+            Cell::Terrain(cost) => cost as f32,
+            _ => 0.
+        }
+    }
+
+    //Cost of a single step between two adjacent cells, entering terrain adds its cost
+    pub fn cost(&self, from: Position, to: Position) -> f32 {
+        let distance = if !self.topology.is_hex() && from.0 != to.0 && from.1 != to.1 {std::f32::consts::SQRT_2} else {1.};
+        distance + self.terrain_cost(to)
+    }
+
+    //Distance between two cell centers, 1 between cells sharing an edge
+    pub fn distance(&self, from: Position, to: Position) -> f32 {
+        let (from, to) = self.closest_images(from, to);
+        match self.topology {
+            Topology::Square => {
+                let (dx, dy) = (from.0.abs_diff(to.0) as f32, from.1.abs_diff(to.1) as f32);
+                (dx*dx + dy*dy).sqrt()
+            },
+            Topology::PointyHex => hex::euclidean_distance(true, from, to),
+            Topology::FlatHex => hex::euclidean_distance(false, from, to),
+        }
+    }
+
+    //Fewest steps between two cells on an empty grid when diagonal steps are allowed
+    pub fn steps(&self, from: Position, to: Position) -> usize {
+        let (from, to) = self.closest_images(from, to);
+        match self.topology {
+            Topology::Square => from.0.abs_diff(to.0).max(from.1.abs_diff(to.1)),
+            Topology::PointyHex => hex::distance(true, from, to),
+            Topology::FlatHex => hex::distance(false, from, to),
+        }
+    }
+
+    //Cells crossed by the segment between two cell centers after the first one, they can be off the grid for hex lines
+    //along its edge. Across a wrapping edge the segment keeps going past it, so the cells are left unfolded
+    pub fn line(&self, from: Position, to: Position) -> Vec<(i32, i32)> {
+        //the line is traced between the closest images and moved back so it starts at from
+        let (from_image, to_image) = self.closest_images(from, to);
+        let shift = (from_image.0 as i32 - from.0 as i32, from_image.1 as i32 - from.1 as i32);
+        let cells = match self.topology {
+            Topology::Square => square_line(from_image, to_image),
+            Topology::PointyHex => hex::line(true, from_image, to_image),
+            Topology::FlatHex => hex::line(false, from_image, to_image),
+        };
+        cells.into_iter().map(|(x, y)| (x - shift.0, y - shift.1)).collect()
+    }
+
+    //Cost of moving straight between two cell centers, the distance plus the terrain of every cell crossed
+    pub fn line_cost(&self, from: Position, to: Position) -> f32 {
+        let terrain: f32 = self.line(from, to).into_iter()
+            .filter_map(|(x, y)| self.wrap_position(x, y))
+            .map(|position| self.terrain_cost(position))
+            .sum();
+        self.distance(from, to) + terrain
+    }
+}
+
+//Square cells crossed by the segment between two cell centers after the first one, passing through a corner steps diagonally
+fn square_line(from: Position, to: Position) -> Vec<(i32, i32)> {
+    let (dx, dy) = ((to.0 as i32 - from.0 as i32).abs(), (to.1 as i32 - from.1 as i32).abs());
+    let (sx, sy) = ((to.0 as i32 - from.0 as i32).signum(), (to.1 as i32 - from.1 as i32).signum());
+    let (mut x, mut y) = (from.0 as i32, from.1 as i32);
+    let (mut ix, mut iy) = (0, 0);
+    let mut cells = Vec::new();
+    while ix < dx || iy < dy {
+        //compare when the segment crosses the next vertical and the next horizontal grid line
+        let decision = (1 + 2*ix)*dy - (1 + 2*iy)*dx;
+        if decision == 0 {
+            x += sx;
+            y += sy;
+            ix += 1;
+            iy += 1;
+        } else if decision < 0 {
+            x += sx;
+            ix += 1;
+        } else {
+            y += sy;
+            iy += 1;
+        }
+        cells.push((x, y));
+    }
+    cells
+}
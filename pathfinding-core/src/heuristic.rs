@@ -0,0 +1,81 @@
+use crate::{hex, HeuristicFn, Movement, Position, Topology};
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum Heuristic {
+    #[default]
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+    Octile,
+    Hex, //steps between hexes
+    Zero
+}
+impl Heuristic {
+    pub const SQUARE: [Heuristic; 5] = [Self::Euclidean, Self::Manhattan, Self::Chebyshev, Self::Octile, Self::Zero];
+    pub const HEX: [Heuristic; 3] = [Self::Hex, Self::Euclidean, Self::Zero];
+
+    //Heuristics that make sense for a topology
+    pub fn options(topology: Topology) -> &'static [Heuristic] {
+        if topology.is_hex() {&Self::HEX} else {&Self::SQUARE}
+    }
+
+    //Heuristics for the other topology stand in with the closest one that fits
+    pub fn get_heuristic(&self, topology: Topology) -> HeuristicFn {
+        match (self, topology) {
+            (Self::Zero, _) => zero_heuristic,
+            (Self::Euclidean, Topology::Square) => euclidean_heuristic,
+            (Self::Manhattan, Topology::Square) => manhattan_heuristic,
+            (Self::Chebyshev, Topology::Square) => chebyshev_heuristic,
+            (Self::Octile | Self::Hex, Topology::Square) => octile_heuristic,
+            (Self::Euclidean, Topology::PointyHex) => pointy_hex_euclidean_heuristic,
+            (Self::Euclidean, Topology::FlatHex) => flat_hex_euclidean_heuristic,
+            (_, Topology::PointyHex) => pointy_hex_heuristic,
+            (_, Topology::FlatHex) => flat_hex_heuristic,
+        }
+    }
+
+    //An admissible heuristic never overestimates the cost of reaching the end, which A* needs to find shortest paths.
+    //Manhattan distance counts a diagonal step as 2 when it only costs sqrt(2), so it only is without diagonal steps
+    pub fn is_admissible(&self, movement: Movement) -> bool {
+        !matches!(self, Self::Manhattan) || !movement.allows_diagonals()
+    }
+}
+
+pub fn euclidean_heuristic((ax, ay): Position, (bx, by): Position) -> f32 {
+    ((ax as f32 - bx as f32).powi(2) + (ay as f32 - by as f32).powi(2)).sqrt()
+}
+
+pub fn manhattan_heuristic((ax, ay): Position, (bx, by): Position) -> f32 {
+    (ax as f32 - bx as f32).abs() + (ay as f32 - by as f32).abs()
+}
+
+pub fn chebyshev_heuristic((ax, ay): Position, (bx, by): Position) -> f32 {
+    (ax as f32 - bx as f32).abs().max((ay as f32 - by as f32).abs())
+}
+
+//Cost of moving between two positions using only straight and diagonal steps
+pub fn octile_heuristic((ax, ay): Position, (bx, by): Position) -> f32 {
+    let (dx, dy) = ((ax as f32 - bx as f32).abs(), (ay as f32 - by as f32).abs());
+    dx.max(dy) - dx.min(dy) + dx.min(dy)*std::f32::consts::SQRT_2
+}
+
+pub fn zero_heuristic(_: Position, _: Position) -> f32 {
+    0.
+}
+
+pub fn pointy_hex_heuristic(a: Position, b: Position) -> f32 {
+    hex::distance(true, a, b) as f32
+}
+
+pub fn flat_hex_heuristic(a: Position, b: Position) -> f32 {
+    hex::distance(false, a, b) as f32
+}
+
+//Distance between hex centers, never more than the steps between them since each step moves 1
+pub fn pointy_hex_euclidean_heuristic(a: Position, b: Position) -> f32 {
+    hex::euclidean_distance(true, a, b)
+}
+
+pub fn flat_hex_euclidean_heuristic(a: Position, b: Position) -> f32 {
+    hex::euclidean_distance(false, a, b)
+}
@@ -0,0 +1,86 @@
+//Hex grids are stored like square ones and indexed by offset coordinates. Pointy-top hexes shift every odd
+//row half a hex towards +x, flat-top hexes shift every odd column half a hex towards +y.
+//Distances and lines are worked out in axial coordinates (q, r), where the 6 neighbors are always the same offsets
+
+use crate::Position;
+
+const SQRT_3: f32 = 1.732_050_8;
+
+pub fn to_axial(pointy: bool, (x, y): (i32, i32)) -> (i32, i32) {
+    if pointy {(x - (y - (y&1))/2, y)} else {(x, y - (x - (x&1))/2)}
+}
+
+pub fn from_axial(pointy: bool, (q, r): (i32, i32)) -> (i32, i32) {
+    if pointy {(q + (r - (r&1))/2, r)} else {(q, r + (q - (q&1))/2)}
+}
+
+//Offsets from a cell to its 6 neighbors, which depend on whether the cell is in a shifted row or column
+pub fn directions(pointy: bool, (x, y): Position) -> [(i32, i32); 6] {
+    let shifted = if pointy {y%2 == 1} else {x%2 == 1};
+    match (pointy, shifted) {
+        (true, false) => [(1, 0), (-1, 0), (-1, 1), (0, 1), (-1, -1), (0, -1)],
+        (true, true) => [(1, 0), (-1, 0), (0, 1), (1, 1), (0, -1), (1, -1)],
+        (false, false) => [(0, 1), (0, -1), (1, 0), (1, -1), (-1, 0), (-1, -1)],
+        (false, true) => [(0, 1), (0, -1), (1, 1), (1, 0), (-1, 1), (-1, 0)],
+    }
+}
+
+//Number of steps between two cells
+pub fn distance(pointy: bool, a: Position, b: Position) -> usize {
+    let (aq, ar) = to_axial(pointy, (a.0 as i32, a.1 as i32));
+    let (bq, br) = to_axial(pointy, (b.0 as i32, b.1 as i32));
+    let (dq, dr) = (aq - bq, ar - br);
+    ((dq.abs() + dr.abs() + (dq + dr).abs())/2) as usize
+}
+
+//Center of a cell, neighboring centers are 1 apart
+pub fn center(pointy: bool, (x, y): Position) -> (f32, f32) {
+    if pointy {
+        (x as f32 + 0.5*(y%2) as f32, y as f32 * SQRT_3/2.)
+    } else {
+        (x as f32 * SQRT_3/2., y as f32 + 0.5*(x%2) as f32)
+    }
+}
+
+//Offset between the centers of a cell and the one a step away from it, the step can leave the grid
+pub fn step_offset(pointy: bool, (x, y): Position, (dx, dy): (i32, i32)) -> (f32, f32) {
+    //half a hex for every shifted row or column the step goes into or out of
+    let shift = |from: usize, d: i32| 0.5*((from as i32 + d).rem_euclid(2) - (from%2) as i32) as f32;
+    if pointy {
+        (dx as f32 + shift(y, dy), dy as f32 * SQRT_3/2.)
+    } else {
+        (dx as f32 * SQRT_3/2., dy as f32 + shift(x, dx))
+    }
+}
+
+pub fn euclidean_distance(pointy: bool, a: Position, b: Position) -> f32 {
+    let ((ax, ay), (bx, by)) = (center(pointy, a), center(pointy, b));
+    ((ax - bx).powi(2) + (ay - by).powi(2)).sqrt()
+}
+
+//Cells crossed by the segment between two cell centers after the first one. The segment is nudged off the corners
+//it passes exactly through, so a cell along the edge of the grid can be just outside it
+pub fn line(pointy: bool, from: Position, to: Position) -> Vec<(i32, i32)> {
+    let steps = distance(pointy, from, to);
+    let (aq, ar) = to_axial(pointy, (from.0 as i32, from.1 as i32));
+    let (bq, br) = to_axial(pointy, (to.0 as i32, to.1 as i32));
+    let (aq, ar) = (aq as f32 + 1e-4, ar as f32 + 1e-4);
+    let (bq, br) = (bq as f32 + 1e-4, br as f32 + 1e-4);
+    (1..=steps).map(|i| {
+        let t = i as f32 / steps as f32;
+        from_axial(pointy, axial_round(aq + (bq - aq)*t, ar + (br - ar)*t))
+    }).collect()
+}
+
+//Axial cell a fractional axial position is in, rounding the third cube coordinate along with q and r
+fn axial_round(q: f32, r: f32) -> (i32, i32) {
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    (rq as i32, rr as i32)
+}
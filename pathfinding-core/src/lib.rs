@@ -0,0 +1,21 @@
+//Pathfinding algorithms over a plain grid, with no dependency on Bevy.
+//Every solver runs one step at a time, returning what each step changed so that a frontend can animate the search.
+
+mod algorithms;
+mod grid;
+mod heuristic;
+mod hex;
+mod solution;
+mod solver;
+
+pub use algorithms::*;
+pub use grid::{Cell, Direction, GridMap, Movement, Topology, Wrap};
+pub use heuristic::Heuristic;
+pub use solution::{path_cost, path_length, SearchStats, Solution, TileState, TileValues, TraceEvent};
+pub use solver::{Solver, SolverEntry, SolverParam, SolverRegistry};
+
+//(x, y) position of a cell in a grid
+pub type Position = (usize, usize);
+
+//Estimate of the cost of moving between two positions
+pub type HeuristicFn = fn(Position, Position) -> f32;
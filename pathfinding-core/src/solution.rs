@@ -0,0 +1,71 @@
+use crate::{GridMap, Position};
+
+//State of a cell in a search, each one is drawn differently by a frontend
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TileState {
+    Unvisited,
+    Open,
+    Closed,
+    ReverseOpen, //open in the backward half of a bidirectional search
+    ReverseClosed, //closed in the backward half of a bidirectional search
+    JumpPoint,
+    Scanned, //crossed by a Jump Point Search scan
+    Path
+}
+
+//Numbers a search computed for a cell, kept so a frontend can show why one cell was chosen over another
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TileValues {
+    pub g: f32, //distance from the start found so far
+    pub h: f32, //heuristic estimate of the distance to the end
+    pub f: f32, //priority the solver orders its open list by
+    pub parent: Option<Position> //cell the search reached this one from
+}
+impl TileValues {
+    pub fn new(g: f32, h: f32, f: f32, parent: Option<Position>) -> Self {
+        Self {g, h, f, parent}
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraceEvent {
+    State(Position, TileState), //a cell changed state
+    Values(Position, TileValues), //the search computed new values for a cell
+    Segment(Position, Position), //a straight piece of an any-angle path
+    Threshold(f32) //IDA* started an iteration with this f-threshold
+}
+
+//Counts a solver keeps while it searches
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SearchStats {
+    pub expanded: usize, //tiles taken off the open list and expanded
+    pub max_open: usize //most tiles waiting in the open list at once
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Solution {
+    pub path: Vec<Position>, //positions from the start to the end, empty if there is no path
+    pub trace: Vec<Vec<TraceEvent>>, //events grouped by the step of the search that produced them
+    pub stats: SearchStats
+}
+impl Solution {
+    pub fn found(&self) -> bool {
+        !self.path.is_empty()
+    }
+}
+
+//Number of tiles a path crosses, the straight segments of any-angle paths count every tile they step through
+//and going through a portal is one step
+pub fn path_length(grid: &GridMap, path: &[Position]) -> usize {
+    if path.is_empty() {return 0}
+    1 + path.windows(2).map(|w| if grid.portal_exit(w[0]) == Some(w[1]) {1} else {grid.steps(w[0], w[1])}).sum::<usize>()
+}
+
+//Length of a path measured between tile centers, so diagonal moves cost √2, plus the terrain it crosses and
+//the cost of the portals it goes through
+pub fn path_cost(grid: &GridMap, path: &[Position]) -> f32 {
+    path.windows(2).map(|w| {
+        //a portal next to the other end can be stepped over too, whichever is cheaper was taken
+        if grid.portal_exit(w[0]) == Some(w[1]) {grid.edge_cost(w[0], w[1])} else {grid.line_cost(w[0], w[1])}
+    }).sum()
+}
@@ -0,0 +1,125 @@
+use std::ops::RangeInclusive;
+
+use crate::{algorithms::*, GridMap, HeuristicFn, Position, SearchStats, Solution, TraceEvent};
+
+//A search that runs one step at a time so it can be animated, paused, or spread over many frames
+pub trait Solver: Send + Sync {
+    //Start a new search of the grid, discarding any previous one
+    fn init(&mut self, grid: &GridMap, heuristic: HeuristicFn);
+
+    //Run one step of the search and return what it changed, or None once the search is finished
+    fn step(&mut self, grid: &GridMap) -> Option<Vec<TraceEvent>>;
+
+    //The path found by the search, empty until it finds one
+    fn result(&self) -> &[Position];
+
+    //Counts of the work done by the search so far
+    fn stats(&self) -> SearchStats;
+
+    //Prepare to update the last search after the cell at position changed, the update is then read with step.
+    //Solvers that can only start over return false
+    fn repair(&mut self, _grid: &GridMap, _position: Position) -> bool {
+        false
+    }
+
+    //Run a whole search at once
+    fn solve(&mut self, grid: &GridMap, heuristic: HeuristicFn) -> Solution {
+        self.init(grid, heuristic);
+        let mut trace = Vec::new();
+        while let Some(events) = self.step(grid) {
+            trace.push(events);
+        }
+        Solution {path: self.result().to_vec(), trace, stats: self.stats()}
+    }
+}
+
+//Tunable number passed to a solver when it is created
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolverParam {
+    pub name: &'static str,
+    pub value: f32,
+    pub range: RangeInclusive<f32>,
+    pub step: f32 //smallest change the value can be set to
+}
+
+//A named way of creating a solver that can be picked from a SolverRegistry
+pub struct SolverEntry {
+    pub name: &'static str,
+    pub uses_heuristic: bool,
+    pub params: Vec<SolverParam>,
+    create: fn(&[f32]) -> Box<dyn Solver> //receives the value of each param in order
+}
+impl SolverEntry {
+    pub fn new(name: &'static str, create: fn(&[f32]) -> Box<dyn Solver>) -> Self {
+        Self {name, uses_heuristic: true, params: Vec::new(), create}
+    }
+
+    pub fn with_param(mut self, name: &'static str, value: f32, range: RangeInclusive<f32>, step: f32) -> Self {
+        self.params.push(SolverParam {name, value, range, step});
+        self
+    }
+
+    //Mark the solver as ignoring the heuristic it is given
+    pub fn without_heuristic(mut self) -> Self {
+        self.uses_heuristic = false;
+        self
+    }
+
+    pub fn create(&self) -> Box<dyn Solver> {
+        let values: Vec<f32> = self.params.iter().map(|param| param.value).collect();
+        self.create_with(&values)
+    }
+
+    //Create the solver with its params set to values instead, one for each param in order
+    pub fn create_with(&self, values: &[f32]) -> Box<dyn Solver> {
+        (self.create)(values)
+    }
+}
+
+//Every solver that can be selected, in the order they are listed
+pub struct SolverRegistry {
+    entries: Vec<SolverEntry>
+}
+impl SolverRegistry {
+    pub fn new() -> Self {
+        Self {entries: Vec::new()}
+    }
+
+    pub fn register(&mut self, entry: SolverEntry) -> &mut Self {
+        self.entries.push(entry);
+        self
+    }
+
+    pub fn entries(&self) -> &[SolverEntry] {
+        &self.entries
+    }
+
+    pub fn entries_mut(&mut self) -> &mut [SolverEntry] {
+        &mut self.entries
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SolverEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+}
+//The registry starts out with the built in solvers
+impl Default for SolverRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register(SolverEntry::new("A*", |_| Box::new(BestFirst::a_star())))
+            .register(SolverEntry::new("Dijkstra", |_| Box::new(BestFirst::dijkstra())).without_heuristic())
+            .register(SolverEntry::new("Breadth-first", |_| Box::new(UninformedSearch::bfs())).without_heuristic())
+            .register(SolverEntry::new("Depth-first", |_| Box::new(UninformedSearch::dfs())).without_heuristic())
+            .register(SolverEntry::new("Greedy best-first", |_| Box::new(BestFirst::greedy())))
+            .register(SolverEntry::new("Weighted A*", |params| Box::new(BestFirst::weighted(params[0]))).with_param("Weight", 2., 1.0..=5.0, 0.1))
+            .register(SolverEntry::new("Beam search", |params| Box::new(BestFirst::beam(params[0] as usize))).with_param("Beam width", 10., 1.0..=50.0, 1.))
+            .register(SolverEntry::new("Bidirectional A*", |_| Box::new(BidirectionalAStar::default())))
+            .register(SolverEntry::new("Jump Point Search", |_| Box::new(Jps::default())))
+            .register(SolverEntry::new("Theta*", |_| Box::new(ThetaStar::default())))
+            .register(SolverEntry::new("Lazy Theta*", |_| Box::new(ThetaStar::lazy())))
+            .register(SolverEntry::new("IDA*", |_| Box::new(IdaStar::default())))
+            .register(SolverEntry::new("LPA*", |_| Box::new(LpaStar::default())));
+        registry
+    }
+}
@@ -0,0 +1,307 @@
+//Solvers checked against Dijkstra's algorithm on small hand-built grids
+
+use pathfinding_core::*;
+
+//Walls and terrain most tests search through
+const MAP: [&str; 4] = [
+    "....#....",
+    ".S.2#.3..",
+    "..#...#E.",
+    "....3.#..",
+];
+
+//Solvers that are meant to always find a path as cheap as the one Dijkstra's algorithm finds
+const OPTIMAL: [&str; 4] = ["A*", "Jump Point Search", "IDA*", "LPA*"];
+
+//Grid from rows of text, the first row is the top so the map reads the way the frontend shows it with y going up.
+//'#' is a wall, a digit is terrain of that cost, an arrow is a one-way cell, and 'S' and 'E' are the start and end
+fn grid(rows: &[&str]) -> GridMap {
+    let (width, height) = (rows[0].len(), rows.len());
+    let mut grid = GridMap::new(width, height, (0, 0), (0, 0));
+    for (y, row) in rows.iter().rev().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            grid.cells[y][x] = match c {
+                '#' => Cell::Wall,
+                '^' => Cell::OneWay(Direction::Up),
+                '>' => Cell::OneWay(Direction::Right),
+                'v' => Cell::OneWay(Direction::Down),
+                '<' => Cell::OneWay(Direction::Left),
+                '1'..='9' => Cell::Terrain(c.to_digit(10).unwrap() as u8),
+                _ => Cell::Empty
+            };
+            if c == 'S' {grid.start = (x, y)}
+            if c == 'E' {grid.end = (x, y)}
+        }
+    }
+    grid
+}
+
+fn solve(grid: &GridMap, name: &str) -> Solution {
+    let heuristic = Heuristic::options(grid.topology)[0].get_heuristic(grid.topology);
+    SolverRegistry::default().get(name).unwrap().create().solve(grid, heuristic)
+}
+
+//A path has to go from the start to the end along edges of the grid, any-angle paths only between walkable cells
+fn assert_valid(grid: &GridMap, path: &[Position], name: &str) {
+    assert_eq!(path.first(), Some(&grid.start), "{name} path doesn't start at the start");
+    assert_eq!(path.last(), Some(&grid.end), "{name} path doesn't reach the end");
+    if name.contains("Theta") {
+        assert!(path.iter().all(|&(x, y)| grid.is_walkable(x as i32, y as i32)), "{name} path goes through a wall");
+        if !grid.topology.is_hex() && !grid.movement.allows_diagonals() {
+            assert!(path.windows(2).all(|line| line[0].0 == line[1].0 || line[0].1 == line[1].1), "{name} path goes diagonally without diagonal steps");
+        }
+        return;
+    }
+    for step in path.windows(2) {
+        assert!(grid.edge_cost(step[0], step[1]).is_finite(), "{name} steps from {:?} to {:?}", step[0], step[1]);
+    }
+}
+
+//A solver has to find a valid path as cheap as the one Dijkstra's algorithm finds
+fn assert_optimal(grid: &GridMap, name: &str) {
+    let shortest = solve(grid, "Dijkstra").path;
+    assert!(!shortest.is_empty(), "the grid has no path to test {name} on");
+    let least = path_cost(grid, &shortest);
+    let path = solve(grid, name).path;
+    assert_valid(grid, &path, name);
+    let cost = path_cost(grid, &path);
+    assert!((cost - least).abs() < 1e-4, "{name} found a path costing {cost} but the least is {least}");
+}
+
+#[test]
+fn ida_star_is_optimal_across_wrapping_edges() {
+    let rows = [
+        "..#.....#...",
+        "S.#..#..#..E",
+        "..#..#......",
+        ".....#..#...",
+    ];
+    for topology in Topology::ALL {
+        for wrap in Wrap::ALL {
+            let mut grid = grid(&rows);
+            grid.topology = topology;
+            grid.wrap = wrap;
+            assert_optimal(&grid, "IDA*");
+        }
+    }
+}
+
+#[test]
+fn ida_star_is_optimal_through_portals() {
+    let mut grid = grid(&[
+        "S...#.......",
+        "....#.......",
+        "....#.......",
+        "....#......E",
+    ]);
+    grid.portals = vec![((1, 0), (10, 3))];
+    for movement in Movement::ALL {
+        for portal_cost in [0., 1., 5.] {
+            grid.movement = movement;
+            grid.portal_cost = portal_cost;
+            assert_optimal(&grid, "IDA*");
+        }
+    }
+}
+
+#[test]
+fn ida_star_finishes_without_a_path() {
+    let mut grid = grid(&[
+        "...#..",
+        ".S.#..",
+        "...#..",
+        "...#..",
+        "...#E.",
+        "...#..",
+    ]);
+    for movement in Movement::ALL {
+        grid.movement = movement;
+        let solution = solve(&grid, "IDA*");
+        assert!(!solution.found(), "IDA* found a path through a wall");
+        assert_eq!(solution.stats.expanded, 0);
+    }
+}
+
+#[test]
+fn every_solver_finds_a_valid_path() {
+    let mut grid = grid(&MAP);
+    for movement in Movement::ALL {
+        grid.movement = movement;
+        for entry in SolverRegistry::default().entries() {
+            assert_valid(&grid, &solve(&grid, entry.name).path, entry.name);
+        }
+    }
+    //any-angle paths on an empty grid are only straight lines when there are no diagonal steps
+    let mut grid = GridMap::new(8, 8, (0, 0), (5, 3));
+    grid.movement = Movement::FourWay;
+    for name in ["Theta*", "Lazy Theta*"] {
+        let path = solve(&grid, name).path;
+        assert_valid(&grid, &path, name);
+        assert_eq!(path_cost(&grid, &path), 8., "{name} path {path:?} is shorter than 4-way steps allow");
+    }
+}
+
+#[test]
+fn optimal_solvers_match_dijkstra() {
+    let mut grid = grid(&MAP);
+    for movement in Movement::ALL {
+        grid.movement = movement;
+        for name in OPTIMAL {
+            assert_optimal(&grid, name);
+        }
+    }
+}
+
+#[test]
+fn optimal_solvers_match_dijkstra_on_hex_grids() {
+    let mut grid = grid(&[
+        "S...#....",
+        ".##.#.2..",
+        "..#...#..",
+        "..#.3.#.E",
+    ]);
+    for topology in [Topology::PointyHex, Topology::FlatHex] {
+        grid.topology = topology;
+        for name in OPTIMAL {
+            assert_optimal(&grid, name);
+        }
+    }
+}
+
+#[test]
+fn optimal_solvers_match_dijkstra_across_wrapping_edges() {
+    let mut grid = grid(&[
+        "..#.....#...",
+        "S.#..#..#..E",
+        "..#..#......",
+        ".....#..#...",
+    ]);
+    for topology in Topology::ALL {
+        for wrap in Wrap::ALL {
+            grid.topology = topology;
+            grid.wrap = wrap;
+            for name in OPTIMAL {
+                assert_optimal(&grid, name);
+            }
+        }
+    }
+}
+
+#[test]
+fn optimal_solvers_match_dijkstra_across_wrapping_edges_of_empty_grids() {
+    let mut grid = GridMap::new(6, 6, (5, 3), (0, 0));
+    grid.movement = Movement::FourWay;
+    for topology in Topology::ALL {
+        for wrap in Wrap::ALL {
+            grid.topology = topology;
+            grid.wrap = wrap;
+            for name in OPTIMAL {
+                assert_optimal(&grid, name);
+            }
+        }
+    }
+}
+
+#[test]
+fn hex_estimates_across_wrapping_edges_are_never_too_high() {
+    for size in [4, 6] {
+        let mut grid = GridMap::new(size, size, (0, 0), (0, 0));
+        for topology in [Topology::PointyHex, Topology::FlatHex] {
+            for wrap in Wrap::ALL {
+                grid.topology = topology;
+                grid.wrap = wrap;
+                let cells: Vec<Position> = (0..size).flat_map(|x| (0..size).map(move |y| (x, y))).collect();
+                for &start in &cells {
+                    for &end in &cells {
+                        grid.start = start;
+                        grid.end = end;
+                        let least = path_cost(&grid, &solve(&grid, "Dijkstra").path);
+                        for heuristic in Heuristic::options(topology) {
+                            let estimate = grid.estimate(heuristic.get_heuristic(topology), start, end);
+                            assert!(estimate <= least + 1e-4, "{heuristic:?} estimates {estimate} from {start:?} to {end:?} but it costs {least} on {topology:?} with {wrap:?} wrapping");
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn optimal_solvers_match_dijkstra_through_portals() {
+    let mut grid = grid(&[
+        "S...#.......",
+        "....#...2...",
+        "....#.......",
+        "....#......E",
+    ]);
+    grid.portals = vec![((1, 0), (6, 3)), ((2, 3), (9, 0))];
+    for movement in Movement::ALL {
+        for portal_cost in [0., 1., 5.] {
+            grid.movement = movement;
+            grid.portal_cost = portal_cost;
+            for name in OPTIMAL {
+                assert_optimal(&grid, name);
+            }
+        }
+    }
+}
+
+#[test]
+fn one_way_cells_are_only_crossed_their_way() {
+    //the gap through the wall only leads left, so the way right goes around the bottom
+    let mut grid = grid(&[
+        "....#....",
+        "S...<...E",
+        "....#....",
+        "....#....",
+        ".........",
+    ]);
+    grid.movement = Movement::FourWay;
+    assert_eq!(path_cost(&grid, &solve(&grid, "Dijkstra").path), 14.);
+    for topology in Topology::ALL {
+        grid.topology = topology;
+        for entry in SolverRegistry::default().entries() {
+            assert_valid(&grid, &solve(&grid, entry.name).path, entry.name);
+        }
+        for name in OPTIMAL {
+            assert_optimal(&grid, name);
+        }
+    }
+}
+
+#[test]
+fn lpa_star_repair_matches_solving_again() {
+    let mut grid = grid(&MAP);
+    let heuristic = Heuristic::Euclidean.get_heuristic(grid.topology);
+    let mut lpa = SolverRegistry::default().get("LPA*").unwrap().create();
+    lpa.solve(&grid, heuristic);
+    let changes = [
+        ((5, 1), Cell::Wall),
+        ((4, 3), Cell::Empty),
+        ((5, 3), Cell::Terrain(7)),
+        ((4, 0), Cell::OneWay(Direction::Left)),
+        ((8, 1), Cell::Wall),
+        ((6, 1), Cell::Empty),
+        ((5, 1), Cell::Empty),
+    ];
+    for ((x, y), cell) in changes {
+        grid.cells[y][x] = cell;
+        assert!(lpa.repair(&grid, (x, y)));
+        while lpa.step(&grid).is_some() {}
+        assert_valid(&grid, lpa.result(), "LPA*");
+        let again = path_cost(&grid, &solve(&grid, "LPA*").path);
+        assert!((path_cost(&grid, lpa.result()) - again).abs() < 1e-4, "repaired path costs {} but solving again costs {again}", path_cost(&grid, lpa.result()));
+    }
+}
+
+#[test]
+fn beam_search_as_wide_as_the_grid_is_a_star() {
+    let grid = grid(&MAP);
+    let heuristic = Heuristic::Euclidean.get_heuristic(grid.topology);
+    let beam = BestFirst::beam(grid.width*grid.height).solve(&grid, heuristic);
+    assert_eq!(beam.path, solve(&grid, "A*").path);
+    //a beam one tile wide can still only take steps that exist
+    let narrow = BestFirst::beam(1).solve(&grid, heuristic);
+    if narrow.found() {assert_valid(&grid, &narrow.path, "Beam search")}
+}
@@ -1,97 +1,174 @@
 
-use crate::solve_buffer::SolveBuffer;
-use crate::solver::SolverState;
+use std::time::{Duration, Instant};
+
+use crate::solve_buffer::{SolveBuffer, SolveInfo, PathLine, SolveStats};
+use crate::solver::{Solvers, SolverState, push_step};
 use crate::tile::*;
 use bevy::prelude::*;
 use bevy::math::*;
+use pathfinding_core::{path_cost, path_length, Cell, GridMap, Movement, Solver, Topology, Wrap};
+
+//Width of a pointy-top hex, or height of a flat-top one, as a fraction of its height
+const HEX_WIDTH: f32 = 0.866_025_4;
 
 pub enum GridEvent {
-    Resize(usize),
+    Resize(usize, usize), //width and height
+    SetCount(usize), //number of grids shown side by side
+    SetMovement(Movement),
+    SetTopology(Topology),
+    SetWrap(Wrap),
+    SetPortalCost(f32),
     Clear,
     Reset,
     Solve,
 }
 
+//Square part of the window left of the gui that the grids are drawn in
+#[derive(Resource, Clone, Copy)]
+pub struct GridArea {
+    pub bottom_left: Vec3,
+    pub size: f32
+}
+impl GridArea {
+    //visual size and translation of grid number index when count grids share the area, in rows of up to 2
+    pub fn layout(&self, index: usize, count: usize) -> (f32, Vec3) {
+        let columns = if count > 1 {2} else {1};
+        let rows = (count + columns - 1) / columns;
+        let cell_size = self.size / columns as f32;
+        let gap = if count > 1 {10.} else {0.};
+        let (column, row) = (index % columns, index / columns);
+        //rows are filled from the top and centered vertically
+        let top = self.size - (self.size - rows as f32 * cell_size)/2.;
+        let translation = self.bottom_left + vec3(column as f32 * cell_size + gap/2., top - (row+1) as f32 * cell_size + gap/2., 0.);
+        (cell_size - gap, translation)
+    }
+}
+
 #[derive(Default, Component)]
 pub struct Grid {
+    pub index: usize, //position among the grids shown side by side
     pub grid: Vec<Vec<Tile>>,
     pub start: (usize, usize),
     pub end: (usize, usize),
-    visual_size: f32,
-    pub grid_size: usize
+    visual_size: f32, //length of the side of the square the grid is drawn in
+    pub width: usize,
+    pub height: usize,
+    pub movement: Movement, //steps between tiles every solver is allowed to take
+    pub topology: Topology, //shape of the tiles
+    pub wrap: Wrap, //edges that lead around to the opposite edge
+    pub portal_cost: f32, //cost of going from one end of a portal pair to the other
+    pub solver: Option<Box<dyn Solver>>, //solver of the shown solution, kept so wall changes can be repaired without solving again
+    pub solving: bool, //whether the solver still has steps to animate
+    solve_time: Duration //time spent in the solver since the solve or repair started
 }
 impl Grid {
     pub fn spawn_grid(
         commands: &mut Commands,
-        mesh_assets: &mut ResMut<Assets<Mesh>>,
-        grid_size: usize, visual_size: f32,
-        translation: Vec3
+        shapes: &TileShapes,
+        (width, height): (usize, usize), visual_size: f32,
+        translation: Vec3,
+        index: usize
     ) {
-        if grid_size==0 {return}
+        if width==0 || height==0 {return}
+        let tile_types: Vec<Vec<TileType>> = (0..height).map(|y| (0..width).map(|x| {
+            if x==1 && y==1 {TileType::Start} else if x==width-2 && y==height-2 {TileType::End} else {TileType::None}
+        }).collect()).collect();
+        Grid::spawn_tiles(commands, shapes, tile_types, ((1, 1), (width-2, height-2)), (Movement::default(), Topology::default(), Wrap::default(), 1.), visual_size, translation, index);
+    }
 
-        let mut grid: Vec<Vec<Tile>> = vec![];
-        let sprite_size = Grid::sprite_size(visual_size, grid_size);
+    //spawn another grid with the same size, tiles, start, end, movement, topology, wrapping, and portal cost as template
+    pub fn spawn_mirror(
+        commands: &mut Commands,
+        shapes: &TileShapes,
+        template: &Grid,
+        visual_size: f32,
+        translation: Vec3,
+        index: usize
+    ) {
+        let tile_types: Vec<Vec<TileType>> = template.grid.iter().map(|row| row.iter().map(|tile| tile.tile_type).collect()).collect();
+        Grid::spawn_tiles(commands, shapes, tile_types, (template.start, template.end), (template.movement, template.topology, template.wrap, template.portal_cost), visual_size, translation, index);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_tiles(
+        commands: &mut Commands,
+        shapes: &TileShapes,
+        tile_types: Vec<Vec<TileType>>,
+        (start, end): ((usize, usize), (usize, usize)),
+        (movement, topology, wrap, portal_cost): (Movement, Topology, Wrap, f32),
+        visual_size: f32,
+        translation: Vec3,
+        index: usize
+    ) {
+        let (width, height) = (tile_types[0].len(), tile_types.len());
+        let mut grid = Grid {index, grid: vec![], start, end, visual_size, width, height, movement, topology, wrap, portal_cost, solver: None, solving: false, solve_time: Duration::ZERO};
+        let sprite_size = grid.tile_size();
 
         commands.spawn(SpriteBundle {
             transform: Transform::from_translation(translation),
             ..default()
         })
         .with_children(|builder| {
-            for y in 0..grid_size {
+            for (y, type_row) in tile_types.iter().enumerate() {
                 let mut row: Vec<Tile> = vec![];
-                for x in 0..grid_size {
-                    let translation: Vec3 = vec3(x as f32 * (sprite_size+0.25), y as f32 * (sprite_size+0.25), 0.) + sprite_size/2.;
-                    let tile_type = if x==1 && y==1 {TileType::Start} else if x==grid_size-2 && x==y {TileType::End} else {TileType::None};
-                    let entity = builder.spawn(VisualTileBundle::new((x, y), translation, sprite_size, mesh_assets, tile_type)).id();
-                    //println!("tile: {:?}, color: {:?}, type: {:?}", (x, y), tile_type.color(), tile_type);
-                    row.push(Tile::new(entity, (x, y), None, tile_type));
+                for (x, &tile_type) in type_row.iter().enumerate() {
+                    let translation = grid.tile_translation((x, y));
+                    let entity = builder.spawn(VisualTileBundle::new((x, y), translation, sprite_size, shapes, topology, tile_type)).id();
+                    row.push(Tile::new(entity, (x, y), tile_type));
                 }
-                grid.push(row);
+                grid.grid.push(row);
             }
-        }).insert(Grid {
-            grid, start: (1, 1), end: (grid_size-2, grid_size-2), visual_size, grid_size
-        });
+        }).insert((
+            grid,
+            //every grid picks its own solver from Solvers and is animated from its own buffer
+            SolverState::default(),
+            SolveBuffer::default(),
+            SolveInfo::default()
+        ));
     }
 
     pub fn resize(
         entity: Entity,
         commands: &mut Commands,
-        mesh_assets: &mut ResMut<Assets<Mesh>>,
+        shapes: &TileShapes,
         grid_query: &mut Query<&mut Grid>,
-        new_size: usize,
+        (new_width, new_height): (usize, usize),
         sprite_query: &mut Query<(&mut Sprite, &mut VisualTile)>
     ) {
-        if new_size < 5 {return}
+        if new_width < 5 || new_height < 5 {return}
         commands.entity(entity).despawn_descendants();
 
         let mut grid = grid_query.get_mut(entity).unwrap();
-        grid.grid.resize(new_size, vec![Tile::default(); new_size]);
+        grid.grid.resize(new_height, vec![Tile::default(); new_width]);
         grid.grid.iter_mut().for_each(|row| {
-            row.resize(new_size, Tile::default());
+            row.resize(new_width, Tile::default());
         });
-        grid.grid_size = new_size;
+        grid.width = new_width;
+        grid.height = new_height;
+        grid.solver = None;
 
         //reset start and end tiles if they were deleted
-        if grid.start.0>=grid.grid_size || grid.start.1>=grid.grid_size {
+        let new_end = (new_width-2, new_height-2);
+        if !grid.in_bounds(grid.start) {
             if grid.end==(1, 1) {
-                grid.set_end((new_size-2, new_size-2), sprite_query);
+                grid.set_end(new_end, sprite_query);
             }
             grid.set_start((1, 1), sprite_query);
-        } else if grid.end.0>=grid.grid_size || grid.end.1>=grid.grid_size {
-            if grid.start==(new_size-2, new_size-2) {
+        } else if !grid.in_bounds(grid.end) {
+            if grid.start==new_end {
                 grid.set_start((1, 1), sprite_query);
             }
-            grid.set_end((new_size-2, new_size-2), sprite_query);
+            grid.set_end(new_end, sprite_query);
         }
 
 
-        let sprite_size = Grid::sprite_size(grid.visual_size, grid.grid_size);
+        let sprite_size = grid.tile_size();
 
         commands.entity(entity).add_children(|builder| {
-            for y in 0..new_size {
-                for x in 0..new_size {
-                    let translation =  vec3(x as f32 * (sprite_size+0.25), y as f32 * (sprite_size+0.25), 0.) + sprite_size/2.;
-                    let e = builder.spawn(VisualTileBundle::new((x, y), translation, sprite_size, mesh_assets, grid.grid[y][x].tile_type)).id();
+            for y in 0..new_height {
+                for x in 0..new_width {
+                    let translation = grid.tile_translation((x, y));
+                    let e = builder.spawn(VisualTileBundle::new((x, y), translation, sprite_size, shapes, grid.topology, grid.grid[y][x].tile_type)).id();
                     grid.grid[y][x].entity = e;
                     grid.grid[y][x].position = (x, y);
                 }
@@ -106,10 +183,12 @@ impl Grid {
         sprite_query: &mut Query<(&mut Sprite, &mut VisualTile)>
     ) {
         let mut grid = grid_query.get_mut(entity).unwrap();
+        grid.solver = None;
         for row in grid.grid.iter_mut() {
             for tile in row.iter_mut() {
-                let (mut sprite, _) = sprite_query.get_mut(tile.entity).unwrap();
+                let (mut sprite, mut visual_tile) = sprite_query.get_mut(tile.entity).unwrap();
                 tile.set_type(tile.tile_type, sprite.as_mut());
+                visual_tile.clear_search();
             }
         }
     }
@@ -123,29 +202,99 @@ impl Grid {
         let mut grid = grid_query.get_mut(entity).unwrap();
         for row in grid.grid.iter_mut() {
             for tile in row.iter_mut() {
-                let (mut sprite, _) = sprite_query.get_mut(tile.entity).unwrap();
+                let (mut sprite, mut visual_tile) = sprite_query.get_mut(tile.entity).unwrap();
                 tile.set_type(TileType::None, sprite.as_mut());
+                visual_tile.clear_search();
             }
         }
-        let grid_size = grid.grid_size;
-        grid.set_end((grid_size-2, grid_size-2), sprite_query);
+        let end = (grid.width-2, grid.height-2);
+        grid.set_end(end, sprite_query);
         grid.set_start((1, 1), sprite_query);
     }
 
+    //start a new solve, its steps are run one at a time by process_update_buffer_system
     pub fn solve(
         entity: Entity,
         grid_query: &mut Query<&mut Grid>,
         solver_state: &SolverState,
-        solve_buffer: &mut SolveBuffer
+        solvers: &Solvers
     ) {
         let mut grid = grid_query.get_mut(entity).unwrap();
-        solver_state.algorithm.get_algorithm()(&mut grid, solve_buffer, solver_state.heuristic.get_heuristic());
+        let mut solver = solver_state.create(solvers);
+        solver.init(&grid.map(), solver_state.heuristic.get_heuristic(grid.topology));
+        grid.solver = Some(solver);
+        grid.solving = true;
+        grid.solve_time = Duration::ZERO;
+    }
+
+    //run the next step of the solver and queue it as a frame of the solve animation
+    pub fn step(&mut self, solve_buffer: &mut SolveBuffer) {
+        if !self.solving {return}
+        let map = self.map();
+        let Some(solver) = self.solver.as_mut() else {return};
+
+        let start_time = Instant::now();
+        let events = solver.step(&map);
+        self.solve_time += start_time.elapsed();
+
+        let Some(events) = events else {
+            self.solving = false;
+            return;
+        };
+        let stats = SolveStats {
+            search: solver.stats(),
+            path_length: path_length(&map, solver.result()),
+            path_cost: path_cost(&map, solver.result()),
+            solve_time: self.solve_time,
+            frames: solve_buffer.frames.len()+1
+        };
+        push_step(self, &events, stats, solve_buffer);
+    }
+
+    //plain copy of the grid for the solvers
+    pub fn map(&self) -> GridMap {
+        let mut map = GridMap::new(self.width, self.height, self.start, self.end);
+        map.movement = self.movement;
+        map.topology = self.topology;
+        map.wrap = self.wrap;
+        map.portal_cost = self.portal_cost;
+        let mut portal_ends: Vec<Vec<(usize, usize)>> = vec![vec![]; MAX_PORTALS as usize];
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                match tile.tile_type {
+                    TileType::Wall => map.cells[y][x] = Cell::Wall,
+                    TileType::Terrain(cost) => map.cells[y][x] = Cell::Terrain(cost),
+                    TileType::OneWay(direction) => map.cells[y][x] = Cell::OneWay(direction),
+                    TileType::Portal(id) => portal_ends[id as usize].push((x, y)),
+                    _ => ()
+                }
+            }
+        }
+        //a portal only leads somewhere once both ends of its pair are placed
+        map.portals = portal_ends.into_iter().filter(|ends| ends.len() == 2).map(|ends| (ends[0], ends[1])).collect();
+        map
+    }
+
+    //number of tiles that are an end of the portal pair with an id
+    pub fn portal_ends(&self, id: u8) -> usize {
+        self.grid.iter().flatten().filter(|tile| tile.tile_type == TileType::Portal(id)).count()
+    }
+
+    //let the solver repair the shown solution after the type of the tile at position changed, returns false if it can't
+    pub fn repair(&mut self, position: (usize, usize)) -> bool {
+        let map = self.map();
+        let Some(solver) = self.solver.as_mut() else {return false};
+        if !solver.repair(&map, position) {return false}
+        self.solving = true;
+        self.solve_time = Duration::ZERO;
+        true
     }
 
     //resets type and color of previous start and sets new start
     pub fn set_start(&mut self, new: (usize, usize), sprite_query: &mut Query<(&mut Sprite, &mut VisualTile)>) {
-        if new.0>=self.grid_size || new.1>=self.grid_size {return;}
-        if self.start.0<self.grid_size && self.start.1<self.grid_size {
+        if !self.in_bounds(new) {return;}
+        self.solver = None;
+        if self.in_bounds(self.start) {
             let (mut sprite, _visual_tile) = sprite_query.get_mut(self.grid[self.start.1][self.start.0].entity).unwrap();
             self.grid[self.start.1][self.start.0].set_type(TileType::None, sprite.as_mut());
         }
@@ -159,8 +308,9 @@ impl Grid {
 
     //resets type and color of previous end and sets new end
     pub fn set_end(&mut self, new: (usize, usize), sprite_query: &mut Query<(&mut Sprite, &mut VisualTile)>) {
-        if new.0>self.grid_size || new.1>self.grid_size {return;}
-        if self.end.0<self.grid_size && self.end.1<self.grid_size {
+        if !self.in_bounds(new) {return;}
+        self.solver = None;
+        if self.in_bounds(self.end) {
             let (mut sprite, _visual_tile) = sprite_query.get_mut(self.grid[self.end.1][self.end.0].entity).unwrap();
             self.grid[self.end.1][self.end.0].set_type(TileType::None, sprite.as_mut());
         }
@@ -172,41 +322,153 @@ impl Grid {
         self.grid[self.end.1][self.end.0]
     }
 
-    //calculate the size of tile sprites
-    pub fn sprite_size(visual_size: f32, grid_size: usize) -> f32 {(visual_size - (grid_size as f32/4.)) / (grid_size as f32)}
+    pub fn in_bounds(&self, (x, y): (usize, usize)) -> bool {x<self.width && y<self.height}
+
+    //width and height of the grid measured in tile sprites plus the gap between them. Hex rows or columns overlap by
+    //a quarter and every other one sticks out by half a hex
+    fn extent(topology: Topology, (width, height): (usize, usize)) -> Vec2 {
+        let (width, height) = (width as f32, height as f32);
+        match topology {
+            Topology::Square => vec2(width, height),
+            Topology::PointyHex => vec2((width + 0.5)*HEX_WIDTH, (height - 1.)*0.75 + 1.),
+            Topology::FlatHex => vec2((width - 1.)*0.75 + 1., (height + 0.5)*HEX_WIDTH),
+        }
+    }
+
+    //calculate the size of tile sprites, the longer side of the grid fills the square it is drawn in
+    pub fn sprite_size(visual_size: f32, grid_size: (usize, usize), topology: Topology) -> f32 {
+        visual_size / Grid::extent(topology, grid_size).max_element() - 0.25
+    }
+    pub fn tile_size(&self) -> f32 {Grid::sprite_size(self.visual_size, (self.width, self.height), self.topology)}
+
+    //calculate the position of a tile's center relative to the grid, the shorter side is centered in the square
+    pub fn tile_translation(&self, (x, y): (usize, usize)) -> Vec3 {
+        let spacing = self.tile_size() + 0.25;
+        let margin = (Vec2::splat(self.visual_size) - Grid::extent(self.topology, (self.width, self.height))*spacing)/2.;
+        let (x, y) = (x as f32, y as f32);
+        //center in tile spacings from the bottom left of the grid, odd hex rows or columns are shifted by half a hex
+        let center = match self.topology {
+            Topology::Square => vec2(x + 0.5, y + 0.5),
+            Topology::PointyHex => vec2((x + 0.5*(y%2.) + 0.5)*HEX_WIDTH, y*0.75 + 0.5),
+            Topology::FlatHex => vec2(x*0.75 + 0.5, (y + 0.5*(x%2.) + 0.5)*HEX_WIDTH),
+        };
+        (margin + center*spacing).extend(0.)
+    }
+
+    //bottom left corner and size of the area one copy of the grid repeats over when it wraps, so tiles past the
+    //right or top edge of it are drawn as if they were the ones on the opposite edge
+    pub fn wrap_area(&self) -> (Vec2, Vec2) {
+        let spacing = self.tile_size() + 0.25;
+        //distance between neighboring columns and rows
+        let step = match self.topology {
+            Topology::Square => Vec2::splat(spacing),
+            Topology::PointyHex => vec2(HEX_WIDTH, 0.75)*spacing,
+            Topology::FlatHex => vec2(0.75, HEX_WIDTH)*spacing,
+        };
+        (self.tile_translation((0, 0)).truncate() - step/2., vec2(self.width as f32, self.height as f32)*step)
+    }
 }
 
 
+#[allow(clippy::too_many_arguments)]
 pub fn process_grid_events(
     mut commands: Commands,
     mut event_reader: EventReader<GridEvent>,
-    grid_entity_query: Query<Entity, With<Grid>>,
     mut grid_query: Query<&mut Grid>,
-    mut mesh_assets: ResMut<Assets<Mesh>>,
+    mut solve_query: Query<(Entity, &SolverState, &mut SolveBuffer, &mut SolveInfo), With<Grid>>,
+    mut transform_query: Query<&mut Transform, With<Grid>>,
+    shapes: Res<TileShapes>,
     mut sprite_query: Query<(&mut Sprite, &mut VisualTile)>,
-    solver_state: Res<SolverState>,
-    mut solve_buffer: ResMut<SolveBuffer>
+    path_line_query: Query<Entity, With<PathLine>>,
+    grid_area: Res<GridArea>,
+    solvers: Res<Solvers>
 ) {
-    let grid_entity = grid_entity_query.get_single().unwrap();
+    //a grid's new tiles can only be queried once the commands spawning them have run, so a solve asked for in the
+    //same frame is started in the next one
+    for (grid_entity, solver_state, _, mut solve_info) in solve_query.iter_mut() {
+        if !solve_info.solve_pending {continue}
+        solve_info.solve_pending = false;
+        Grid::clear(grid_entity, &mut grid_query, &mut sprite_query);
+        Grid::solve(grid_entity, &mut grid_query, solver_state, &solvers);
+        solve_info.solved = true;
+    }
+    let mut respawned: Vec<Entity> = Vec::new();
+
     for event in event_reader.iter() {
-        solve_buffer.0.clear();
-        match *event {
-            GridEvent::Resize(size) => {
-                Grid::resize(grid_entity, &mut commands, &mut mesh_assets, &mut grid_query, size, &mut sprite_query);
-            },
-            GridEvent::Clear => {
-                Grid::clear(grid_entity, &mut grid_query, &mut sprite_query);
-            },
-            GridEvent::Reset => {
-                Grid::reset(grid_entity, &mut grid_query, &mut sprite_query);
-            },
-            GridEvent::Solve => {
-                Grid::clear(grid_entity, &mut grid_query, &mut sprite_query);
-                Grid::solve(grid_entity, &mut grid_query, solver_state.as_ref(), solve_buffer.as_mut());
+        for path_line in path_line_query.iter() {
+            commands.entity(path_line).despawn_recursive();
+        }
+        //every grid mirrors the others so each event applies to all of them
+        for (grid_entity, solver_state, mut solve_buffer, mut solve_info) in solve_query.iter_mut() {
+            solve_buffer.clear();
+            *solve_info = SolveInfo::default();
+            match *event {
+                GridEvent::Resize(width, height) => {
+                    Grid::resize(grid_entity, &mut commands, &shapes, &mut grid_query, (width, height), &mut sprite_query);
+                    respawned.push(grid_entity);
+                },
+                GridEvent::SetCount(count) => {
+                    let grid = grid_query.get(grid_entity).unwrap();
+                    if grid.index >= count {
+                        commands.entity(grid_entity).despawn_recursive();
+                        continue;
+                    }
+                    //the grids that stay are redrawn at their new size
+                    let (visual_size, translation) = grid_area.layout(grid.index, count);
+                    let grid_size = (grid.width, grid.height);
+                    grid_query.get_mut(grid_entity).unwrap().visual_size = visual_size;
+                    transform_query.get_mut(grid_entity).unwrap().translation = translation;
+                    Grid::resize(grid_entity, &mut commands, &shapes, &mut grid_query, grid_size, &mut sprite_query);
+                    respawned.push(grid_entity);
+                },
+                GridEvent::SetMovement(movement) => {
+                    Grid::clear(grid_entity, &mut grid_query, &mut sprite_query);
+                    grid_query.get_mut(grid_entity).unwrap().movement = movement;
+                },
+                GridEvent::SetPortalCost(portal_cost) => {
+                    Grid::clear(grid_entity, &mut grid_query, &mut sprite_query);
+                    grid_query.get_mut(grid_entity).unwrap().portal_cost = portal_cost;
+                },
+                GridEvent::SetWrap(wrap) => {
+                    Grid::clear(grid_entity, &mut grid_query, &mut sprite_query);
+                    grid_query.get_mut(grid_entity).unwrap().wrap = wrap;
+                },
+                GridEvent::SetTopology(topology) => {
+                    //the tiles are spawned again in their new shape
+                    let mut grid = grid_query.get_mut(grid_entity).unwrap();
+                    grid.topology = topology;
+                    let grid_size = (grid.width, grid.height);
+                    Grid::resize(grid_entity, &mut commands, &shapes, &mut grid_query, grid_size, &mut sprite_query);
+                    respawned.push(grid_entity);
+                },
+                GridEvent::Clear => {
+                    Grid::clear(grid_entity, &mut grid_query, &mut sprite_query);
+                },
+                GridEvent::Reset => {
+                    Grid::reset(grid_entity, &mut grid_query, &mut sprite_query);
+                },
+                GridEvent::Solve if respawned.contains(&grid_entity) => {
+                    solve_info.solve_pending = true;
+                    solve_buffer.playing = true;
+                },
+                GridEvent::Solve => {
+                    //a new solve is animated even if the last one was paused by stepping through it
+                    solve_buffer.playing = true;
+                    Grid::clear(grid_entity, &mut grid_query, &mut sprite_query);
+                    Grid::solve(grid_entity, &mut grid_query, solver_state, &solvers);
+                    solve_info.solved = true;
+                }
+            }
+        }
+
+        //new grids copy the first one
+        if let GridEvent::SetCount(count) = *event {
+            let Some(template) = grid_query.iter().find(|grid| grid.index == 0) else {continue};
+            let current_count = grid_query.iter().count();
+            for index in current_count..count {
+                let (visual_size, translation) = grid_area.layout(index, count);
+                Grid::spawn_mirror(&mut commands, &shapes, template, visual_size, translation, index);
             }
         }
     }
 }
-
-
-
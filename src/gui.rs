@@ -2,16 +2,29 @@ use std::time::Duration;
 
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContext};
+use bevy_mod_picking::Hover;
 
-use crate::{grid::GridEvent, solve_buffer::UpdateTimer};
+use crate::{grid::{Grid, GridEvent}, overlay::OverlaySettings, solve_buffer::{UpdateTimer, SolveInfo, SolveBuffer}, solver::{Solvers, SolverState, Heuristic, Movement, Topology, Wrap}, tile::{Brush, BrushKind, VisualTile, MAX_PORTALS, MAX_TERRAIN_COST, PORTAL_COLORS}};
 
 
+#[allow(clippy::too_many_arguments)]
 pub fn gui(
     mut ctx: ResMut<EguiContext>,
     mut grid_event_writer: EventWriter<GridEvent>,
-    grid_size: &mut usize,
+    grid_size: &mut (usize, usize), //width and height
     solve_speed_divisor: &mut f32,
-    mut update_timer: ResMut<UpdateTimer>
+    grid_count: &mut usize,
+    selected_grid: &mut usize, //index of the grid whose solver is edited
+    movement: &mut Movement,
+    topology: &mut Topology,
+    wrap: &mut Wrap,
+    portal_cost: &mut f32,
+    mut update_timer: ResMut<UpdateTimer>,
+    mut grid_query: Query<(&Grid, &mut SolverState, &mut SolveBuffer, &SolveInfo)>,
+    mut overlay_settings: ResMut<OverlaySettings>,
+    mut brush: ResMut<Brush>,
+    tile_query: Query<(&VisualTile, &Hover, &Parent)>,
+    solvers: Res<Solvers>
 ) {
     use crate::gui::egui::TextStyle::{Heading, Body, Monospace, Small, Button};
     use crate::gui::egui::FontFamily::{Proportional};
@@ -53,12 +66,150 @@ pub fn gui(
                 });
                 ui.add_space(25.);
 
-                //Grid size slider
+                //Grid width and height sliders, rounded up to an even size where hexes wrap around
+                let (even_width, even_height) = even_size(*topology, *wrap);
                 ui.horizontal(|ui| {
-                    ui.label("Grid Size: ");
-                    let range_slider = ui.add(egui::Slider::new(grid_size, 5..=100).step_by(1.));
+                    ui.label("Width: ");
+                    ui.add_space(38.);
+                    let range_slider = ui.add(egui::Slider::new(&mut grid_size.0, 5..=100).step_by(1.));
                     if range_slider.drag_started() || range_slider.changed() {
-                        grid_event_writer.send(GridEvent::Resize(*grid_size));
+                        if even_width {grid_size.0 += grid_size.0 % 2}
+                        grid_event_writer.send(GridEvent::Resize(grid_size.0, grid_size.1));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Height: ");
+                    ui.add_space(33.);
+                    let range_slider = ui.add(egui::Slider::new(&mut grid_size.1, 5..=100).step_by(1.));
+                    if range_slider.drag_started() || range_slider.changed() {
+                        if even_height {grid_size.1 += grid_size.1 % 2}
+                        grid_event_writer.send(GridEvent::Resize(grid_size.0, grid_size.1));
+                    }
+                });
+                ui.add_space(25.);
+
+                //Number of grids solved side by side
+                ui.horizontal(|ui| {
+                    ui.label("Grids: ");
+                    ui.add_space(42.);
+                    let count_slider = ui.add(egui::Slider::new(grid_count, 1..=4));
+                    if count_slider.changed() {
+                        grid_event_writer.send(GridEvent::SetCount(*grid_count));
+                    }
+                });
+                *selected_grid = (*selected_grid).min(*grid_count-1);
+                ui.add_space(25.);
+
+                //What clicking and dragging over the grid paints
+                ui.horizontal(|ui| {
+                    ui.label("Brush: ");
+                    ui.add_space(40.);
+                    ui.selectable_value(&mut brush.kind, BrushKind::Wall, "Wall");
+                    ui.selectable_value(&mut brush.kind, BrushKind::Terrain, "Terrain");
+                    ui.selectable_value(&mut brush.kind, BrushKind::Portal, "Portal");
+                    ui.selectable_value(&mut brush.kind, BrushKind::OneWay, "One-way");
+                });
+                match brush.kind {
+                    BrushKind::Terrain => {
+                        ui.add(egui::Slider::new(&mut brush.cost, 1..=MAX_TERRAIN_COST).text("cost"));
+                    },
+                    //pair the next portal placed belongs to, shown in its color
+                    BrushKind::Portal => {
+                        ui.horizontal(|ui| {
+                            let mut pair = brush.portal + 1;
+                            ui.add(egui::Slider::new(&mut pair, 1..=MAX_PORTALS).text("pair"));
+                            brush.portal = pair - 1;
+                            let color = PORTAL_COLORS[brush.portal as usize];
+                            let (swatch, _) = ui.allocate_exact_size(egui::vec2(20., 20.), egui::Sense::hover());
+                            ui.painter().rect_filled(swatch, 2., egui::Color32::from_rgb((color.r()*255.) as u8, (color.g()*255.) as u8, (color.b()*255.) as u8));
+                        });
+                    },
+                    BrushKind::OneWay => {
+                        ui.label("Click a one-way tile again to turn it");
+                    },
+                    BrushKind::Wall => ()
+                }
+                ui.add_space(25.);
+
+                //Shape of the tiles, hex tiles always have the same 6 neighbors
+                let previous_topology = *topology;
+                ui.horizontal(|ui| {
+                    ui.label("Tiles: ");
+                    ui.add_space(49.);
+                    egui::ComboBox::from_id_source("topology")
+                        .selected_text(topology_name(*topology))
+                        .show_ui(ui, |ui| {
+                            for option in Topology::ALL {
+                                ui.selectable_value(topology, option, topology_name(option));
+                            }
+                        });
+                });
+                if *topology != previous_topology {
+                    let solved = grid_query.iter().any(|(_, _, _, solve_info)| solve_info.solved);
+                    //heuristics made for the other shape are swapped for the first one made for this one
+                    let options = Heuristic::options(*topology);
+                    grid_query.for_each_mut(|(_, mut solver_state, _, _)| {
+                        if !options.contains(&solver_state.heuristic) {solver_state.heuristic = options[0]}
+                    });
+                    grid_event_writer.send(GridEvent::SetTopology(*topology));
+                    if solved {grid_event_writer.send(GridEvent::Solve)}
+                }
+
+                //Steps between tiles every solver is allowed to take
+                let previous_movement = *movement;
+                ui.add_enabled_ui(!topology.is_hex(), |ui| ui.horizontal(|ui| {
+                    ui.label("Movement: ");
+                    egui::ComboBox::from_id_source("movement")
+                        .selected_text(movement_name(*movement))
+                        .show_ui(ui, |ui| {
+                            for option in Movement::ALL {
+                                ui.selectable_value(movement, option, movement_name(option));
+                            }
+                        });
+                }));
+                if *movement != previous_movement {
+                    let solved = grid_query.iter().any(|(_, _, _, solve_info)| solve_info.solved);
+                    grid_event_writer.send(GridEvent::SetMovement(*movement));
+                    if solved {grid_event_writer.send(GridEvent::Solve)}
+                }
+
+                //Edges that lead around to the opposite edge
+                let previous_wrap = *wrap;
+                ui.horizontal(|ui| {
+                    ui.label("Wrap: ");
+                    ui.add_space(47.);
+                    egui::ComboBox::from_id_source("wrap")
+                        .selected_text(wrap_name(*wrap))
+                        .show_ui(ui, |ui| {
+                            for option in Wrap::ALL {
+                                ui.selectable_value(wrap, option, wrap_name(option));
+                            }
+                        });
+                });
+                if *wrap != previous_wrap {
+                    let solved = grid_query.iter().any(|(_, _, _, solve_info)| solve_info.solved);
+                    grid_event_writer.send(GridEvent::SetWrap(*wrap));
+                    if solved {grid_event_writer.send(GridEvent::Solve)}
+                }
+                //a new tile shape or wrap can need an even size, the grids are resized after the other changes are made
+                let (even_width, even_height) = even_size(*topology, *wrap);
+                if (even_width && grid_size.0 % 2 == 1) || (even_height && grid_size.1 % 2 == 1) {
+                    if even_width {grid_size.0 += 1}
+                    if even_height {grid_size.1 += 1}
+                    let solved = grid_query.iter().any(|(_, _, _, solve_info)| solve_info.solved);
+                    grid_event_writer.send(GridEvent::Resize(grid_size.0, grid_size.1));
+                    if solved {grid_event_writer.send(GridEvent::Solve)}
+                }
+
+                //Cost of going from one end of a portal pair to the other
+                ui.horizontal(|ui| {
+                    ui.label("Portal\nCost: ");
+                    ui.add_space(18.);
+                    let cost_slider = ui.add(egui::Slider::new(portal_cost, 0.0..=10.0).step_by(0.5));
+                    if cost_slider.changed() {
+                        let solved = grid_query.iter().any(|(_, _, _, solve_info)| solve_info.solved);
+                        grid_event_writer.send(GridEvent::SetPortalCost(*portal_cost));
+                        if solved {grid_event_writer.send(GridEvent::Solve)}
                     }
                 });
                 ui.add_space(25.);
@@ -73,7 +224,183 @@ pub fn gui(
                     }
                 });
                 ui.add_space(25.);
+
+                //Play/Pause and single step buttons, stepping pauses the animation. Every grid is moved together
+                let playing = grid_query.iter().any(|(_, _, solve_buffer, _)| solve_buffer.playing);
+                let position = grid_query.iter().map(|(_, _, solve_buffer, _)| solve_buffer.position()).max().unwrap_or(0);
+                let mut seek: Option<usize> = None;
+                ui.horizontal(|ui| {
+                    ui.add_space(35.);
+                    if ui.button(if playing {"Pause"} else {"Play"}).clicked() {
+                        grid_query.for_each_mut(|(_, _, mut solve_buffer, _)| solve_buffer.playing = !playing);
+                    }
+                    ui.add_space(25.);
+                    if ui.button("<").clicked() {
+                        seek = Some(position.saturating_sub(1));
+                    }
+                    ui.add_space(25.);
+                    if ui.button(">").clicked() {
+                        seek = Some(position+1);
+                    }
+                });
+
+                //Timeline of every frame made so far
+                let frame_count = grid_query.iter().map(|(_, _, solve_buffer, _)| solve_buffer.frames.len()).max().unwrap_or(0);
+                ui.horizontal(|ui| {
+                    ui.label("Frame: ");
+                    ui.add_space(10.);
+                    let mut frame = position;
+                    let frame_slider = ui.add(egui::Slider::new(&mut frame, 0..=frame_count));
+                    if frame_slider.changed() {
+                        seek = Some(frame);
+                    }
+                });
+                if let Some(frame) = seek {
+                    grid_query.for_each_mut(|(_, _, mut solve_buffer, _)| {
+                        solve_buffer.playing = false;
+                        solve_buffer.seek = Some(frame);
+                    });
+                }
+                ui.add_space(25.);
+
+                //Overlay toggles
+                ui.checkbox(&mut overlay_settings.show_values, "Show g, h, and f on tiles");
+                ui.checkbox(&mut overlay_settings.show_parents, "Show parent arrows");
+                ui.add_space(25.);
+
+                //Grid whose solver the widgets below show
+                if *grid_count > 1 {
+                    ui.horizontal(|ui| {
+                        ui.label("Editing grid: ");
+                        for index in 0..*grid_count {
+                            ui.selectable_value(selected_grid, index, format!("{}", index+1));
+                        }
+                    });
+                    ui.add_space(25.);
+                }
+                let Some((_, mut solver_state, _, solve_info)) = grid_query.iter_mut().find(|(grid, ..)| grid.index == *selected_grid) else {return};
+
+                //Algorithm selector and its parameters
+                let previous_choice = (solver_state.selected, solver_state.params(&solvers), solver_state.heuristic);
+                ui.horizontal(|ui| {
+                    ui.label("Algorithm: ");
+                    egui::ComboBox::from_id_source("algorithm")
+                        .selected_text(solver_state.entry(&solvers).name)
+                        .width(200.)
+                        .show_ui(ui, |ui| {
+                            for (index, entry) in solvers.0.entries().iter().enumerate() {
+                                ui.selectable_value(&mut solver_state.selected, index, entry.name);
+                            }
+                        });
+                });
+                let entry = solver_state.entry(&solvers);
+                for (param, value) in entry.params.iter().zip(solver_state.params_mut(&solvers)) {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{}: ", param.name));
+                        ui.add_space(10.);
+                        ui.add(egui::Slider::new(value, param.range.clone()).step_by(param.step as f64));
+                    });
+                }
+                ui.add_space(25.);
+
+                //Heuristic selector
+                let uses_heuristic = entry.uses_heuristic;
+                ui.add_enabled_ui(uses_heuristic, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Heuristic: ");
+                        egui::ComboBox::from_id_source("heuristic")
+                            .selected_text(format!("{:?}", solver_state.heuristic))
+                            .show_ui(ui, |ui| {
+                                for &heuristic in Heuristic::options(*topology) {
+                                    ui.selectable_value(&mut solver_state.heuristic, heuristic, format!("{:?}", heuristic));
+                                }
+                            });
+                    });
+                });
+                if uses_heuristic && !topology.is_hex() && !solver_state.heuristic.is_admissible(*movement) {
+                    ui.colored_label(egui::Color32::RED, format!("{:?} overestimates diagonal moves,\npaths may not be the shortest", solver_state.heuristic));
+                }
+                ui.add_space(25.);
+
+                //show the new choice right away if a solution is on screen
+                if (solver_state.selected, solver_state.params(&solvers), solver_state.heuristic) != previous_choice && solve_info.solved {
+                    grid_event_writer.send(GridEvent::Solve);
+                }
+
+                //IDA* f-threshold of the iteration being animated
+                if let Some(threshold) = solve_info.threshold {
+                    ui.label(format!("f-threshold: {:.2}", threshold));
+                    ui.add_space(25.);
+                }
+
+                //Stats of the solve up to the frame being shown
+                if let Some(stats) = solve_info.stats {
+                    ui.label(format!("Nodes expanded: {}", stats.search.expanded));
+                    ui.label(format!("Max open list size: {}", stats.search.max_open));
+                    if stats.path_length > 0 {
+                        ui.label(format!("Path length: {} tiles", stats.path_length));
+                        ui.label(format!("Path cost: {:.3}", stats.path_cost));
+                    } else {
+                        ui.label("Path length: -");
+                        ui.label("Path cost: -");
+                    }
+                    ui.label(format!("Solve time: {:.3} ms", stats.solve_time.as_secs_f64()*1000.));
+                    ui.label(format!("Frames: {}", stats.frames));
+                    ui.add_space(25.);
+                }
             });
+
+            //Inspector for the tile under the mouse
+            let Some((visual_tile, _, parent)) = tile_query.iter().find(|(_, hover, _)| hover.hovered()) else {return};
+            let Ok((grid, ..)) = grid_query.get(parent.get()) else {return};
+            //the tile can be gone for a frame while the grid is resized
+            let Some(tile) = grid.grid.get(visual_tile.y).and_then(|row| row.get(visual_tile.x)) else {return};
+            ui.add_space(25.);
+            ui.label(format!("Tile ({}, {}) on grid {}", visual_tile.x, visual_tile.y, grid.index+1));
+            ui.label(format!("Type: {:?}", tile.tile_type));
+            ui.label(format!("State: {:?}", visual_tile.state));
+            match visual_tile.values {
+                Some(values) => {
+                    ui.label(format!("Parent: {}", values.parent.map_or("-".to_string(), |(x, y)| format!("({}, {})", x, y))));
+                    ui.label(format!("g: {:.2}   h: {:.2}   f: {:.2}", values.g, values.h, values.f));
+                },
+                None => {
+                    ui.label("Parent: -");
+                    ui.label("g: -   h: -   f: -");
+                }
+            }
         }
     );
+}
+
+fn movement_name(movement: Movement) -> &'static str {
+    match movement {
+        Movement::FourWay => "4-way",
+        Movement::EightWay => "8-way",
+        Movement::NoCornerCutting => "8-way, no corner cutting",
+        Movement::NoSqueezing => "8-way, no squeezing",
+    }
+}
+
+fn topology_name(topology: Topology) -> &'static str {
+    match topology {
+        Topology::Square => "Square",
+        Topology::PointyHex => "Hex, pointy-top",
+        Topology::FlatHex => "Hex, flat-top",
+    }
+}
+
+//Whether the width and height have to be even, shifted hex rows or columns only line up with the ones across a
+//wrapping edge if there is an even number of them
+fn even_size(topology: Topology, wrap: Wrap) -> (bool, bool) {
+    (topology == Topology::FlatHex && wrap.wraps_x(), topology == Topology::PointyHex && wrap.wraps_y())
+}
+
+fn wrap_name(wrap: Wrap) -> &'static str {
+    match wrap {
+        Wrap::None => "None",
+        Wrap::Horizontal => "Left and right",
+        Wrap::Vertical => "Top and bottom",
+        Wrap::Both => "Both",
+    }
 }
\ No newline at end of file
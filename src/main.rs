@@ -4,23 +4,31 @@
 
 use bevy::prelude::*;
 use bevy_egui::EguiContext;
-use bevy_mod_picking::{DefaultPickingPlugins, PickingCameraBundle};
+use bevy_mod_picking::{DefaultPickingPlugins, Hover, PickingCameraBundle};
 use gui::*;
 use grid::*;
-use solve_buffer::{SolveBuffer, process_update_buffer_system, UpdateTimer};
-use solver::SolverState;
+use solve_buffer::{SolveBuffer, SolveInfo, process_update_buffer_system, UpdateTimer};
+use solver::{Movement, Solvers, SolverState, Topology, Wrap};
 use tile::*;
+use overlay::{OverlaySettings, draw_overlay_system};
 
 mod gui;
 mod grid;
 mod tile;
 mod solver;
 mod solve_buffer;
+mod overlay;
 
 fn main() {
 
-    let mut default_grid_size: usize = 20;
+    let mut default_grid_size: (usize, usize) = (20, 20); //width and height
     let mut solve_speed_divisor: f32 = 1.;
+    let mut grid_count: usize = 1;
+    let mut movement = Movement::default();
+    let mut topology = Topology::default();
+    let mut wrap = Wrap::default();
+    let mut portal_cost: f32 = 1.;
+    let mut selected_grid: usize = 0;
 
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
@@ -34,16 +42,19 @@ fn main() {
             ..default()
         }))
         .insert_resource(ClearColor(Color::rgb(0.5, 0.5, 0.5)))
-        .init_resource::<SolverState>()
-        .init_resource::<SolveBuffer>()
         .init_resource::<UpdateTimer>()
+        .init_resource::<OverlaySettings>()
+        .init_resource::<Brush>()
+        .init_resource::<TileShapes>()
+        .init_resource::<Solvers>()
         .add_event::<GridEvent>()
         .add_plugins(DefaultPickingPlugins)
         .add_plugin(bevy_egui:: EguiPlugin)
         .add_startup_system(init)
-        .add_system(move |ctx: ResMut<EguiContext>, grid_event_writer: EventWriter<GridEvent>, mut update_timer: ResMut<UpdateTimer>| {
-            gui(ctx, grid_event_writer, &mut default_grid_size, &mut solve_speed_divisor, update_timer);
+        .add_system(move |ctx: ResMut<EguiContext>, grid_event_writer: EventWriter<GridEvent>, update_timer: ResMut<UpdateTimer>, grid_query: Query<(&Grid, &mut SolverState, &mut SolveBuffer, &SolveInfo)>, overlay_settings: ResMut<OverlaySettings>, brush: ResMut<Brush>, tile_query: Query<(&VisualTile, &Hover, &Parent)>, solvers: Res<Solvers>| {
+            gui(ctx, grid_event_writer, &mut default_grid_size, &mut solve_speed_divisor, &mut grid_count, &mut selected_grid, &mut movement, &mut topology, &mut wrap, &mut portal_cost, update_timer, grid_query, overlay_settings, brush, tile_query, solvers);
         })
+        .add_system(draw_overlay_system)
         .add_system(process_grid_events)
         .add_system(process_tile_click_events)
         .add_system(process_update_buffer_system)
@@ -52,13 +63,15 @@ fn main() {
 
 fn init(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
+    shapes: Res<TileShapes>,
     windows: Res<Windows>,
 ) {
     let window = windows.get_primary().expect("Failed to find primary window");
     commands.spawn((Camera2dBundle::default(), PickingCameraBundle::default()));
 
     //create the grid state and visual tiles
-    let bottom_left = bevy::math::vec3(-1.*window.width()/2., -1.*window.height()/2., 0.);
-    Grid::spawn_grid(&mut commands, &mut meshes, 20, window.height(), bottom_left);
+    let grid_area = GridArea {bottom_left: bevy::math::vec3(-1.*window.width()/2., -1.*window.height()/2., 0.), size: window.height()};
+    let (visual_size, translation) = grid_area.layout(0, 1);
+    Grid::spawn_grid(&mut commands, &shapes, (20, 20), visual_size, translation, 0);
+    commands.insert_resource(grid_area);
 }
@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+use crate::{grid::Grid, tile::{TileType, VisualTile}};
+
+//Optional layers drawn over the grids
+#[derive(Resource, Default)]
+pub struct OverlaySettings {
+    pub show_values: bool,
+    pub show_parents: bool //arrow from each explored tile toward its parent
+}
+
+//Smallest tile in pixels that g, h, and f are written in, they can't be read on smaller tiles
+const MIN_VALUES_TILE_SIZE: f32 = 30.;
+const PARENT_ARROW_COLOR: egui::Color32 = egui::Color32::from_rgb(41, 50, 65);
+
+//Bevy has no font to draw text with, so the overlay is painted by egui underneath its windows.
+//The arrows on one-way tiles are always painted, the other layers only when they are turned on
+pub fn draw_overlay_system(
+    mut ctx: ResMut<EguiContext>,
+    settings: Res<OverlaySettings>,
+    tile_query: Query<(&VisualTile, &Sprite, &GlobalTransform, &Parent)>,
+    grid_query: Query<&Grid>,
+    windows: Res<Windows>
+) {
+    let Some(window) = windows.get_primary() else {return};
+    let painter = ctx.ctx_mut().layer_painter(egui::LayerId::new(egui::Order::Background, egui::Id::new("grid overlay")));
+
+    for (visual_tile, sprite, transform, parent) in tile_query.iter() {
+        let Ok(grid) = grid_query.get(parent.get()) else {continue};
+        let tile_size = grid.tile_size();
+        let center = world_to_screen(window, transform.translation());
+        //light marks on dark tiles and dark ones on light tiles
+        let background = sprite.color;
+        let mark_color = if background.r() + background.g() + background.b() < 1.5 {egui::Color32::WHITE} else {egui::Color32::BLACK};
+
+        //an arrow across the tile the way it can be crossed, the tile can be gone for a frame while the grid is resized
+        if let Some(TileType::OneWay(direction)) = grid.grid.get(visual_tile.y).and_then(|row| row.get(visual_tile.x)).map(|tile| tile.tile_type) {
+            let (dx, dy) = direction.vector();
+            let direction = egui::vec2(dx as f32, -dy as f32);
+            painter.arrow(center - direction*tile_size*0.3, direction*tile_size*0.6, egui::Stroke::new((tile_size/10.).max(1.), mark_color));
+        }
+
+        let Some(values) = visual_tile.values else {continue};
+
+        //the arrow points the way the parent lies, screen y goes down while grid y goes up
+        if let Some(parent_position) = values.parent.filter(|_| settings.show_parents) {
+            //on a wrapping grid the parent can be across the edge, so the arrow points at the copy of it that is closest
+            let (tile, parent) = grid.wrap.closest_images(grid.topology, (grid.width, grid.height), (visual_tile.x, visual_tile.y), parent_position);
+            let offset = grid.tile_translation(parent) - grid.tile_translation(tile);
+            let direction = egui::vec2(offset.x, -offset.y).normalized();
+            painter.arrow(center, direction * tile_size*0.7, egui::Stroke::new((tile_size/15.).max(1.), PARENT_ARROW_COLOR));
+        }
+
+        if !settings.show_values || tile_size < MIN_VALUES_TILE_SIZE {continue}
+        painter.text(
+            center,
+            egui::Align2::CENTER_CENTER,
+            format!("g {:.1}\nh {:.1}\nf {:.1}", values.g, values.h, values.f),
+            egui::FontId::proportional(tile_size/5.),
+            mark_color
+        );
+    }
+}
+
+//The camera is centered on the origin with one world unit per pixel while egui measures from the top left
+fn world_to_screen(window: &Window, translation: Vec3) -> egui::Pos2 {
+    egui::pos2(translation.x + window.width()/2., window.height()/2. - translation.y)
+}
@@ -1,37 +1,233 @@
-use std::collections::VecDeque;
+use std::time::Duration;
 
 use bevy::prelude::*;
+use pathfinding_core::{SearchStats, TileState, TileValues};
 
-use crate::tile::VisualTile;
+use crate::{grid::Grid, tile::{TileType, VisualTile, PATH_COLOR, tile_color}};
 
-#[derive(Resource, Default)]
-pub struct SolveBuffer(pub VecDeque<Vec<TileColorEvent>>);
+//Every frame of the solve animation so far, along with what is needed to undo the ones that are shown
+#[derive(Component)]
+pub struct SolveBuffer {
+    pub frames: Vec<Vec<SolveEvent>>,
+    undo: Vec<Vec<SolveEvent>>, //events that undo each shown frame
+    pub playing: bool,
+    pub seek: Option<usize> //frame the animation was asked to move to
+}
+impl Default for SolveBuffer {
+    fn default() -> Self {
+        Self {frames: Vec::new(), undo: Vec::new(), playing: true, seek: None}
+    }
+}
 impl SolveBuffer {
-    pub fn process_frame(&mut self, sprite_query: &mut Query<&mut Sprite, With<VisualTile>>) {
-        if let Some(event_vec) = self.0.pop_front() {
-            for event in event_vec {event.apply(sprite_query)}
+    //number of frames currently shown
+    pub fn position(&self) -> usize {self.undo.len()}
+
+    //forget every frame, anything they drew is expected to be cleared by the caller
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.undo.clear();
+        self.seek = None;
+    }
+
+    //show the next frame if there is one, returns false otherwise
+    pub fn step_forward(
+        &mut self,
+        commands: &mut Commands,
+        sprite_query: &mut Query<(&mut Sprite, &mut VisualTile)>,
+        grid: (Entity, &Grid),
+        solve_info: &mut SolveInfo
+    ) -> bool {
+        let Some(event_vec) = self.frames.get(self.undo.len()) else {return false};
+        let mut undo_vec: Vec<SolveEvent> = event_vec.iter().map(|event| event.apply(commands, sprite_query, grid, solve_info)).collect();
+        //undo events run in reverse so a tile changed twice in one frame ends up with its first state
+        undo_vec.reverse();
+        self.undo.push(undo_vec);
+        true
+    }
+
+    //undo the last shown frame if there is one, returns false otherwise
+    pub fn step_back(
+        &mut self,
+        commands: &mut Commands,
+        sprite_query: &mut Query<(&mut Sprite, &mut VisualTile)>,
+        grid: (Entity, &Grid),
+        solve_info: &mut SolveInfo
+    ) -> bool {
+        let Some(undo_vec) = self.undo.pop() else {return false};
+        for event in undo_vec {event.apply(commands, sprite_query, grid, solve_info);}
+        true
+    }
+}
+
+//Information about the solve being animated that is shown in the gui
+#[derive(Component, Default)]
+pub struct SolveInfo {
+    pub solved: bool, //whether the grid is showing a solve
+    pub solve_pending: bool, //solve asked for while the grid's tiles were being spawned again, started the next frame
+    pub threshold: Option<f32>,
+    pub stats: Option<SolveStats>
+}
+
+//Numbers about a solve up to the frame being shown
+#[derive(Clone, Copy, Debug)]
+pub struct SolveStats {
+    pub search: SearchStats,
+    pub path_length: usize, //tiles on the path, 0 until one is found
+    pub path_cost: f32,
+    pub solve_time: Duration, //time spent running the solver, not animating it
+    pub frames: usize
+}
+
+#[derive(Clone, Debug)]
+pub enum SolveEvent {
+    TileState(TileStateEvent),
+    TileValues(TileValuesEvent),
+    PathLine(PathLineEvent),
+    RemovePathLine(Vec<Entity>, PathLineEvent), //lines spawned by the PathLineEvent
+    Threshold(Option<f32>),
+    Stats(Option<SolveStats>)
+}
+impl SolveEvent {
+    pub fn state(sprite_entity: Entity, tile_type: TileType, state: TileState) -> Self {
+        Self::TileState(TileStateEvent {sprite_entity, tile_type, state})
+    }
+
+    pub fn values(sprite_entity: Entity, values: TileValues) -> Self {
+        Self::TileValues(TileValuesEvent {sprite_entity, values: Some(values)})
+    }
+
+    pub fn line(from: (usize, usize), to: (usize, usize)) -> Self {
+        Self::PathLine(PathLineEvent::new(from, to))
+    }
+
+    //apply the event and return the event that undoes it
+    pub fn apply(
+        &self,
+        commands: &mut Commands,
+        sprite_query: &mut Query<(&mut Sprite, &mut VisualTile)>,
+        grid: (Entity, &Grid),
+        solve_info: &mut SolveInfo
+    ) -> SolveEvent {
+        match self {
+            Self::TileState(event) => Self::TileState(event.apply(sprite_query)),
+            Self::TileValues(event) => Self::TileValues(event.apply(sprite_query)),
+            Self::PathLine(event) => Self::RemovePathLine(event.apply(commands, grid), event.clone()),
+            Self::RemovePathLine(lines, event) => {
+                for line in lines {
+                    commands.entity(*line).despawn_recursive();
+                }
+                Self::PathLine(event.clone())
+            },
+            Self::Threshold(threshold) => Self::Threshold(std::mem::replace(&mut solve_info.threshold, *threshold)),
+            Self::Stats(stats) => Self::Stats(std::mem::replace(&mut solve_info.stats, *stats)),
         }
     }
 }
 
-#[derive(Debug)]
-pub struct TileColorEvent {
+//Change of a tile's state in the search, which sets its color
+#[derive(Clone, Debug)]
+pub struct TileStateEvent {
     pub sprite_entity: Entity,
-    pub color: Color
+    pub tile_type: TileType, //type of the tile when the step was made, terrain is shaded by its cost
+    pub state: TileState
 }
-impl TileColorEvent {
-    pub fn new(sprite_entity: Entity, color: Color) -> Self {
-        Self {
-            sprite_entity,
-            color
+impl TileStateEvent {
+    //returns the event that sets the tile back to its previous state
+    pub fn apply(&self, sprite_query: &mut Query<(&mut Sprite, &mut VisualTile)>) -> TileStateEvent {
+        let (mut sprite, mut visual_tile) = sprite_query.get_mut(self.sprite_entity).unwrap();
+        sprite.color = tile_color(self.tile_type, self.state);
+        let previous = std::mem::replace(&mut visual_tile.state, self.state);
+        TileStateEvent {sprite_entity: self.sprite_entity, tile_type: self.tile_type, state: previous}
+    }
+}
+
+//New g, h, f, and parent of a tile, None clears them
+#[derive(Clone, Debug)]
+pub struct TileValuesEvent {
+    pub sprite_entity: Entity,
+    pub values: Option<TileValues>
+}
+impl TileValuesEvent {
+    //returns the event that sets the tile's previous values back
+    pub fn apply(&self, sprite_query: &mut Query<(&mut Sprite, &mut VisualTile)>) -> TileValuesEvent {
+        let (_, mut visual_tile) = sprite_query.get_mut(self.sprite_entity).unwrap();
+        let previous = std::mem::replace(&mut visual_tile.values, self.values);
+        TileValuesEvent {sprite_entity: self.sprite_entity, values: previous}
+    }
+}
+
+//Straight segment of an any-angle path, drawn between the centers of two tiles
+#[derive(Clone, Debug)]
+pub struct PathLineEvent {
+    pub from: (usize, usize),
+    pub to: (usize, usize)
+}
+impl PathLineEvent {
+    pub fn new(from: (usize, usize), to: (usize, usize)) -> Self {
+        Self {from, to}
+    }
+
+    //returns the spawned lines so they can be removed again
+    pub fn apply(&self, commands: &mut Commands, (grid_entity, grid): (Entity, &Grid)) -> Vec<Entity> {
+        let tile_size = grid.tile_size();
+        //a segment across a wrapping edge runs to the copy of its end past the edge, and is drawn once leaving the
+        //grid and once moved back a whole grid coming in from the other side, cut off at the edges both times
+        let (from, to) = grid.wrap.closest_images(grid.topology, (grid.width, grid.height), self.from, self.to);
+        let (from, to) = (grid.tile_translation(from).truncate(), grid.tile_translation(to).truncate());
+        let (corner, size) = grid.wrap_area();
+        let bounds = |wraps: bool, corner: f32, size: f32| if wraps {(corner, corner + size)} else {(f32::NEG_INFINITY, f32::INFINITY)};
+        let (x_bounds, y_bounds) = (bounds(grid.wrap.wraps_x(), corner.x, size.x), bounds(grid.wrap.wraps_y(), corner.y, size.y));
+        let x_shifts: &[f32] = if grid.wrap.wraps_x() {&[0., size.x]} else {&[0.]};
+        let y_shifts: &[f32] = if grid.wrap.wraps_y() {&[0., size.y]} else {&[0.]};
+
+        let mut lines = vec![];
+        for &x_shift in x_shifts {
+            for &y_shift in y_shifts {
+                let shift = Vec2::new(x_shift, y_shift);
+                let Some((from, to)) = clip_segment((from - shift, to - shift), Vec2::new(x_bounds.0, y_bounds.0), Vec2::new(x_bounds.1, y_bounds.1)) else {continue};
+                let delta = to - from;
+                let line = commands.spawn((
+                    PathLine,
+                    SpriteBundle {
+                        sprite: Sprite {color: PATH_COLOR, ..default()},
+                        transform: Transform {
+                            //draw the line above the tiles
+                            translation: ((from + to)/2.).extend(1.),
+                            rotation: Quat::from_rotation_z(delta.y.atan2(delta.x)),
+                            scale: Vec3::new(delta.length(), tile_size/4., 1.)
+                        },
+                        ..default()
+                    }
+                )).id();
+                commands.entity(grid_entity).add_child(line);
+                lines.push(line);
+            }
         }
+        lines
     }
+}
 
-    pub fn apply(self, sprite_query: &mut Query<&mut Sprite, With<VisualTile>>) {
-        sprite_query.get_mut(self.sprite_entity).unwrap().color = self.color;
+//Part of the segment between two points inside a box, None if none of it is
+fn clip_segment((from, to): (Vec2, Vec2), min: Vec2, max: Vec2) -> Option<(Vec2, Vec2)> {
+    let delta = to - from;
+    //fractions of the way along the segment where it enters and leaves the box
+    let (mut enter, mut leave) = (0f32, 1f32);
+    for (toward, room) in [(-delta.x, from.x - min.x), (delta.x, max.x - from.x), (-delta.y, from.y - min.y), (delta.y, max.y - from.y)] {
+        if toward == 0. {
+            if room < 0. {return None}
+        } else if toward < 0. {
+            enter = enter.max(room/toward);
+        } else {
+            leave = leave.min(room/toward);
+        }
     }
+    (enter < leave).then(|| (from + delta*enter, from + delta*leave))
 }
 
+//Marks the sprites spawned by PathLineEvents so they can be removed when the grid changes
+#[derive(Component)]
+pub struct PathLine;
+
 
 #[derive(Resource)]
 pub struct UpdateTimer(pub Timer);
@@ -43,14 +239,41 @@ impl Default for UpdateTimer {
 
 
 pub fn process_update_buffer_system(
+    mut commands: Commands,
     mut timer: ResMut<UpdateTimer>,
     time: Res<Time>,
-    mut solve_buffer: ResMut<SolveBuffer>,
-    mut sprite_query: Query<&mut Sprite, With<VisualTile>>
+    mut sprite_query: Query<(&mut Sprite, &mut VisualTile)>,
+    mut grid_query: Query<(Entity, &mut Grid, &mut SolveBuffer, &mut SolveInfo)>
 ) {
     timer.0.tick(time.delta());
+    let frames = timer.0.times_finished_this_tick();
+
+    //every grid moves through its own frames in lockstep with the others
+    for (grid_entity, mut grid, mut solve_buffer, mut solve_info) in grid_query.iter_mut() {
+        //move to the frame picked in the gui, running the solver further if it hasn't been made yet
+        if let Some(target) = solve_buffer.seek.take() {
+            while solve_buffer.position() > target && solve_buffer.step_back(&mut commands, &mut sprite_query, (grid_entity, &grid), solve_info.as_mut()) {}
+            while solve_buffer.position() < target && next_frame(&mut commands, solve_buffer.as_mut(), &mut sprite_query, (grid_entity, grid.as_mut()), solve_info.as_mut()) {}
+        }
 
-    for _ in 0..timer.0.times_finished_this_tick() {
-        solve_buffer.process_frame(&mut sprite_query);
+        if !solve_buffer.playing {continue}
+        for _ in 0..frames {
+            next_frame(&mut commands, solve_buffer.as_mut(), &mut sprite_query, (grid_entity, grid.as_mut()), solve_info.as_mut());
+        }
     }
-}
\ No newline at end of file
+}
+
+//Show the next frame, solvers run one step per frame of the animation instead of all at once so they
+//only run once every frame they made so far is shown
+fn next_frame(
+    commands: &mut Commands,
+    solve_buffer: &mut SolveBuffer,
+    sprite_query: &mut Query<(&mut Sprite, &mut VisualTile)>,
+    (grid_entity, grid): (Entity, &mut Grid),
+    solve_info: &mut SolveInfo
+) -> bool {
+    if solve_buffer.position() == solve_buffer.frames.len() {
+        grid.step(solve_buffer);
+    }
+    solve_buffer.step_forward(commands, sprite_query, (grid_entity, grid), solve_info)
+}
@@ -1,13 +1,18 @@
 use bevy::prelude::*;
 use bevy::math::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 use bevy_mod_picking::*;
+use pathfinding_core::{Direction, TileState, TileValues, Topology};
 
-use crate::grid::Grid;
+use crate::grid::{Grid, GridEvent};
 
-#[derive(Clone, Copy, Default, Debug)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
 pub enum TileType {
     #[default]
-    None, Start, End, Wall
+    None, Start, End, Wall,
+    Terrain(u8), //walkable, but costs this much more to enter than a None tile
+    Portal(u8), //one end of the portal pair with this id, from 0 to MAX_PORTALS-1
+    OneWay(Direction) //walkable, but only entered and left going the direction, like a conveyor belt or a ledge
 }
 impl TileType {
     pub fn color(&self) -> Color {
@@ -16,6 +21,9 @@ impl TileType {
             TileType::Wall => WALL_COLOR,
             TileType::Start => START_COLOR,
             TileType::End => END_COLOR,
+            TileType::Terrain(cost) => terrain_color(cost),
+            TileType::Portal(id) => PORTAL_COLORS[id as usize % PORTAL_COLORS.len()],
+            TileType::OneWay(_) => ONE_WAY_COLOR,
         }
     }
 }
@@ -25,25 +33,113 @@ pub const START_COLOR: Color = Color::SEA_GREEN;
 pub const END_COLOR: Color = Color::RED;
 pub const CLOSED_COLOR: Color = Color::rgb(152./255., 193./255., 217./255.);
 pub const OPEN_COLOR: Color = Color::rgb(61./255., 90./255., 128./255.);
+pub const REVERSE_CLOSED_COLOR: Color = Color::rgb(203./255., 186./255., 237./255.);
+pub const REVERSE_OPEN_COLOR: Color = Color::rgb(106./255., 76./255., 147./255.);
+pub const JUMP_POINT_COLOR: Color = Color::rgb(42./255., 157./255., 143./255.);
+pub const SCAN_COLOR: Color = Color::rgb(233./255., 216./255., 166./255.);
 pub const PATH_COLOR: Color = Color::rgb(238./255., 108./255., 77./255.);
+pub const LIGHT_TERRAIN_COLOR: Color = Color::rgb(233./255., 196./255., 106./255.);
+pub const HEAVY_TERRAIN_COLOR: Color = Color::rgb(120./255., 72./255., 38./255.);
+pub const ONE_WAY_COLOR: Color = Color::rgb(141./255., 153./255., 174./255.);
+pub const MAX_TERRAIN_COST: u8 = 9;
+pub const MAX_PORTALS: u8 = 8;
+//Both ends of a portal pair share a color so they can be told apart from other pairs
+pub const PORTAL_COLORS: [Color; MAX_PORTALS as usize] = [
+    Color::rgb(155./255., 89./255., 182./255.),
+    Color::rgb(0., 188./255., 212./255.),
+    Color::rgb(233./255., 30./255., 99./255.),
+    Color::rgb(139./255., 195./255., 74./255.),
+    Color::rgb(63./255., 81./255., 181./255.),
+    Color::rgb(1., 193./255., 7./255.),
+    Color::rgb(0., 150./255., 136./255.),
+    Color::rgb(244./255., 143./255., 177./255.),
+];
+
+//Terrain goes from sand to mud as it gets more expensive
+pub fn terrain_color(cost: u8) -> Color {
+    let t = (cost.max(1)-1) as f32 / (MAX_TERRAIN_COST-1) as f32;
+    let (light, heavy) = (LIGHT_TERRAIN_COLOR, HEAVY_TERRAIN_COLOR);
+    Color::rgb(light.r() + (heavy.r()-light.r())*t, light.g() + (heavy.g()-light.g())*t, light.b() + (heavy.b()-light.b())*t)
+}
+
+//Color a tile shows for its state in a search
+pub fn state_color(state: TileState) -> Color {
+    match state {
+        TileState::Unvisited => BG_COLOR,
+        TileState::Open => OPEN_COLOR,
+        TileState::Closed => CLOSED_COLOR,
+        TileState::ReverseOpen => REVERSE_OPEN_COLOR,
+        TileState::ReverseClosed => REVERSE_CLOSED_COLOR,
+        TileState::JumpPoint => JUMP_POINT_COLOR,
+        TileState::Scanned => SCAN_COLOR,
+        TileState::Path => PATH_COLOR,
+    }
+}
+
+//Color a tile of some type shows for its state in a search, searched terrain is darkened by its cost so it stays visible.
+//Searched one-way tiles keep their arrows, so they are colored like any other tile
+pub fn tile_color(tile_type: TileType, state: TileState) -> Color {
+    match (tile_type, state) {
+        (TileType::Terrain(_) | TileType::OneWay(_), TileState::Unvisited) => tile_type.color(),
+        (TileType::Terrain(cost), _) => {
+            let color = state_color(state);
+            let shade = 1. - 0.5*cost as f32/MAX_TERRAIN_COST as f32;
+            Color::rgb(color.r()*shade, color.g()*shade, color.b()*shade)
+        },
+        _ => state_color(state)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BrushKind {
+    Wall, Terrain, Portal, OneWay
+}
+
+//What clicking and dragging over a grid paints
+#[derive(Resource)]
+pub struct Brush {
+    pub kind: BrushKind,
+    pub cost: u8, //cost of the terrain painted
+    pub portal: u8 //id of the portal pair placed
+}
+impl Default for Brush {
+    fn default() -> Self {
+        Self {kind: BrushKind::Wall, cost: 3, portal: 0}
+    }
+}
+impl Brush {
+    pub fn tile_type(&self) -> TileType {
+        match self.kind {
+            BrushKind::Wall => TileType::Wall,
+            BrushKind::Terrain => TileType::Terrain(self.cost),
+            BrushKind::Portal => TileType::Portal(self.portal),
+            BrushKind::OneWay => TileType::OneWay(Direction::default()),
+        }
+    }
+
+    //move on to the first portal pair from the current one that doesn't have both ends placed, returns false if every pair does
+    fn next_free_portal(&mut self, grid: &Grid) -> bool {
+        let Some(id) = (0..MAX_PORTALS).map(|i| (self.portal + i) % MAX_PORTALS).find(|&id| grid.portal_ends(id) < 2) else {return false};
+        self.portal = id;
+        true
+    }
+}
 
 
 #[derive(Clone, Copy)]
 pub struct Tile {
     pub entity: Entity, //Entity containing the sprite to be rendered
     pub position: (usize, usize), //(x, y) position of the tile in the grid
-    pub parent: Option<(usize, usize)>, //Optional (x, y) position of the tile's parent
     pub tile_type: TileType,
 }
 impl Tile {
-    pub fn new(entity: Entity, position: (usize, usize), parent: Option<(usize, usize)>, tile_type: TileType) -> Self {
-        Self {entity, position, parent, tile_type}
+    pub fn new(entity: Entity, position: (usize, usize), tile_type: TileType) -> Self {
+        Self {entity, position, tile_type}
     }
     pub fn default() -> Self {
         Self {
             entity: Entity::from_bits(0),
             position: (0, 0),
-            parent: None,
             tile_type: TileType::default()
         }
     }
@@ -54,19 +150,26 @@ impl Tile {
         sprite.color = new_type.color();
     }
 
-    //Swap tile between wall and none if new_type is None, do not overwrite start or end
-    pub fn click(&mut self, mut sprite: &mut Sprite, new_type: Option<TileType>) -> TileType {
+    //Swap tile between the brush's type and none if new_type is None, do not overwrite start or end.
+    //A portal brush removes any portal it clicks, and a one-way brush turns one-way tiles clockwise until they are
+    //back to the brush's direction, where they are removed instead
+    pub fn click(&mut self, mut sprite: &mut Sprite, new_type: Option<TileType>, brush: TileType) -> TileType {
         match new_type {
             None => {
-                self.tile_type = match self.tile_type {
-                    TileType::None => TileType::Wall,
-                    TileType::Wall => TileType::None,
+                self.tile_type = match (self.tile_type, brush) {
+                    (TileType::OneWay(direction), TileType::OneWay(_)) => {
+                        let turned = TileType::OneWay(direction.clockwise());
+                        if turned == brush {TileType::None} else {turned}
+                    },
+                    (tile_type, _) if tile_type == brush => TileType::None,
+                    (TileType::Portal(_), TileType::Portal(_)) => TileType::None,
+                    (TileType::None | TileType::Wall | TileType::Terrain(_) | TileType::Portal(_) | TileType::OneWay(_), _) => brush,
                     _ => self.tile_type 
                 };
             },
             Some(new_type) => {
                 self.tile_type = match self.tile_type {
-                    TileType::Wall | TileType::None => new_type,
+                    TileType::Wall | TileType::None | TileType::Terrain(_) | TileType::Portal(_) | TileType::OneWay(_) => new_type,
                     _ => self.tile_type,
                 }
             }
@@ -79,12 +182,53 @@ impl Tile {
 
 #[derive(Component)]
 pub struct VisualTile {
-    pub x: usize, pub y: usize
+    pub x: usize, pub y: usize,
+    pub state: TileState, //state in the solve being shown
+    pub values: Option<TileValues> //g, h, f, and parent in the solve being shown
 }
 impl VisualTile {
     pub fn new(position: (usize, usize)) -> Self {
-        Self {x: position.0, y: position.1}
+        Self {x: position.0, y: position.1, state: TileState::Unvisited, values: None}
+    }
+
+    //forget everything the last solve showed on this tile
+    pub fn clear_search(&mut self) {
+        self.state = TileState::Unvisited;
+        self.values = None;
+    }
+}
+
+//Meshes tiles are picked with and the texture hex tiles are drawn with, shared by every tile
+#[derive(Resource)]
+pub struct TileShapes {
+    square: Handle<Mesh>,
+    hex: Handle<Mesh>, //pointy-top, flat-top tiles are rotated
+    hex_texture: Handle<Image> //white pointy-top hex the sprite color tints
+}
+impl FromWorld for TileShapes {
+    fn from_world(world: &mut World) -> Self {
+        let (square, hex) = {
+            let mut mesh_assets = world.resource_mut::<Assets<Mesh>>();
+            (mesh_assets.add(Mesh::from(shape::Quad::default())), mesh_assets.add(Mesh::from(shape::RegularPolygon::new(0.5, 6))))
+        };
+        let hex_texture = world.resource_mut::<Assets<Image>>().add(hex_image(64));
+        Self {square, hex, hex_texture}
+    }
+}
+
+//Square image of a pointy-top hex as tall as the image, with transparent corners
+fn hex_image(size: u32) -> Image {
+    let mut data = Vec::with_capacity((size*size*4) as usize);
+    for py in 0..size {
+        for px in 0..size {
+            //distance from the center in hex radii
+            let x = ((px as f32 + 0.5)/size as f32 - 0.5).abs()*2.;
+            let y = ((py as f32 + 0.5)/size as f32 - 0.5).abs()*2.;
+            let inside = x <= 3f32.sqrt()/2. && y <= 1. - x/3f32.sqrt();
+            data.extend_from_slice(&[255, 255, 255, if inside {255} else {0}]);
+        }
     }
+    Image::new(Extent3d {width: size, height: size, depth_or_array_layers: 1}, TextureDimension::D2, data, TextureFormat::Rgba8UnormSrgb)
 }
 
 #[derive(Bundle)]
@@ -95,14 +239,21 @@ pub struct VisualTileBundle {
     pickable_bundle: PickableBundle,
 }
 impl VisualTileBundle {
-    pub fn new(position: (usize, usize), translation: Vec3, size: f32, mesh_assets: &mut ResMut<Assets<Mesh>>, tile_type: TileType) -> Self {
+    pub fn new(position: (usize, usize), translation: Vec3, size: f32, shapes: &TileShapes, topology: Topology, tile_type: TileType) -> Self {
         let visual_tile = VisualTile::new(position);
-        let sprite_bundle = SpriteBundle {
-            sprite: Sprite {color: tile_type.color(), ..default()},
-            transform: Transform { translation, scale: vec3(size, size, 1.), ..default() },
+        //flat-top hexes are pointy-top ones turned a twelfth of the way around
+        let rotation = if topology == Topology::FlatHex {Quat::from_rotation_z(std::f32::consts::PI/6.)} else {Quat::IDENTITY};
+        let mut sprite_bundle = SpriteBundle {
+            sprite: Sprite {color: tile_type.color(), custom_size: Some(Vec2::ONE), ..default()},
+            transform: Transform { translation, rotation, scale: vec3(size, size, 1.) },
             ..default()
         };
-        let mesh = mesh_assets.add(Mesh::from(shape::Quad::default()));
+        let mesh = if topology.is_hex() {
+            sprite_bundle.texture = shapes.hex_texture.clone();
+            shapes.hex.clone()
+        } else {
+            shapes.square.clone()
+        };
         let pickable_bundle = PickableBundle::default();
         Self { visual_tile, sprite_bundle, mesh, pickable_bundle }
     }
@@ -113,28 +264,45 @@ pub fn process_tile_click_events(
     mut sprite_query: Query<(&mut Sprite, &mut VisualTile)>,
     mut grid_query: Query<&mut Grid>,
     mut hover_tile_type: Local<TileType>,
-    mouse_state: Res<Input<MouseButton>>
+    mut brush: ResMut<Brush>,
+    mouse_state: Res<Input<MouseButton>>,
+    mut grid_event_writer: EventWriter<GridEvent>
 ) {
 
     if !mouse_state.pressed(MouseButton::Left) {*hover_tile_type = TileType::default()}
-    let mut grid = grid_query.get_single_mut().unwrap();
     for event in event_reader.iter() {
         match event {
             PickingEvent::Clicked(e) => {
-                let (mut sprite, visual_tile) = sprite_query.get_mut(*e).unwrap();
-                *hover_tile_type = grid.grid[visual_tile.y][visual_tile.x].click(sprite.as_mut(), None);
+                let (_, visual_tile) = sprite_query.get(*e).unwrap();
+                let position = (visual_tile.x, visual_tile.y);
+                //a portal pair only has two ends, so a new portal goes to the next pair that isn't placed yet
+                let Some(grid) = grid_query.iter().next() else {continue};
+                let placing_portal = brush.kind == BrushKind::Portal && matches!(grid.grid[position.1][position.0].tile_type, TileType::None | TileType::Wall | TileType::Terrain(_) | TileType::OneWay(_));
+                if placing_portal && !brush.next_free_portal(grid) {continue}
+                let (new_type, restart) = click_all(&mut grid_query, &mut sprite_query, position, None, brush.tile_type());
+                *hover_tile_type = new_type;
+                if restart {grid_event_writer.send(GridEvent::Solve)}
+                if placing_portal {
+                    let Some(grid) = grid_query.iter().next() else {continue};
+                    brush.next_free_portal(grid);
+                }
             },
             PickingEvent::Hover(hover_event) => {
                 match hover_event {
                     HoverEvent::JustEntered(e) => {
                         if !mouse_state.pressed(MouseButton::Left) {continue}
-                        let (mut sprite, visual_tile) = sprite_query.get_mut(*e).unwrap();
-                        if (visual_tile.x, visual_tile.y) == grid.start || (visual_tile.x, visual_tile.y) == grid.end {continue}
+                        let (_, visual_tile) = sprite_query.get(*e).unwrap();
+                        let position = (visual_tile.x, visual_tile.y);
+                        let Some(grid) = grid_query.iter().next() else {continue};
+                        if position == grid.start || position == grid.end {continue}
                         match *hover_tile_type {
-                            TileType::End => grid.set_end((visual_tile.x, visual_tile.y), &mut sprite_query),
-                            TileType::Start => grid.set_start((visual_tile.x, visual_tile.y), &mut sprite_query),
-                            TileType::None | TileType::Wall => {
-                                grid.grid[visual_tile.y][visual_tile.x].click(sprite.as_mut(), Some(*hover_tile_type));
+                            TileType::End => grid_query.for_each_mut(|mut grid| grid.set_end(position, &mut sprite_query)),
+                            TileType::Start => grid_query.for_each_mut(|mut grid| grid.set_start(position, &mut sprite_query)),
+                            //portals are placed one end at a time
+                            TileType::Portal(_) => (),
+                            TileType::None | TileType::Wall | TileType::Terrain(_) | TileType::OneWay(_) => {
+                                let (_, restart) = click_all(&mut grid_query, &mut sprite_query, position, Some(*hover_tile_type), brush.tile_type());
+                                if restart {grid_event_writer.send(GridEvent::Solve)}
                             }
                         }
                     },
@@ -146,3 +314,22 @@ pub fn process_tile_click_events(
     }
 }
 
+//Click the tile at position on every grid so they keep mirroring each other. Returns the tile's new type and
+//whether a solve in progress has to start over because a solver couldn't repair the change
+fn click_all(
+    grid_query: &mut Query<&mut Grid>,
+    sprite_query: &mut Query<(&mut Sprite, &mut VisualTile)>,
+    (x, y): (usize, usize),
+    new_type: Option<TileType>,
+    brush: TileType
+) -> (TileType, bool) {
+    let mut tile_type = TileType::default();
+    let mut restart = false;
+    for mut grid in grid_query.iter_mut() {
+        let (mut sprite, _) = sprite_query.get_mut(grid.grid[y][x].entity).unwrap();
+        let old_type = grid.grid[y][x].tile_type;
+        tile_type = grid.grid[y][x].click(sprite.as_mut(), new_type, brush);
+        if tile_type != old_type && !grid.repair((x, y)) && grid.solving {restart = true}
+    }
+    (tile_type, restart)
+}